@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use advent_of_code::solutions::yr2024::day04::SolverDay04;
+use advent_of_code::solver::{Solution, Solver};
+
+const DIRECTIONS: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/**
+ * Both parts with each letter looked up through its row's chars().nth, which is how LetterGrid
+ * found letters before it had a flat buffer, walking along the row for every lookup
+ */
+fn solve_by_rows(rows: &[&str]) -> Solution
+{
+    let get = |x: isize, y: isize| -> Option<char> {
+        rows.get(usize::try_from(y).ok()?)?.chars().nth(usize::try_from(x).ok()?)
+    };
+    let is_mas = |ends: (Option<char>, Option<char>)| matches!(ends, (Some('M'), Some('S')) | (Some('S'), Some('M')));
+
+    let mut solution = Solution::default();
+    for (y, row) in rows.iter().enumerate()
+    {
+        for x in 0..row.chars().count()
+        {
+            let (x, y) = (x as isize, y as isize);
+            for (dx, dy) in DIRECTIONS
+            {
+                solution.part1 += "XMAS".chars().enumerate().all(|(step, c)| get(x + dx * step as isize, y + dy * step as isize) == Some(c)) as isize;
+            }
+            if get(x, y) == Some('A')
+            {
+                let crossed = is_mas((get(x - 1, y - 1), get(x + 1, y + 1))) && is_mas((get(x + 1, y - 1), get(x - 1, y + 1)));
+                solution.part2 += crossed as isize;
+            }
+        }
+    }
+    solution
+}
+
+// The real 140x140 input, solved as the solver does, with a flat buffer searched as bytes, and by row lookups
+fn solve(c: &mut Criterion)
+{
+    let input = std::fs::read_to_string("input/2024/day04.txt").unwrap();
+    let rows: Vec<&str> = input.lines().filter(|row| !row.is_empty()).collect();
+    let (solved, by_rows) = (SolverDay04::solve(Box::new(input.lines())).unwrap(), solve_by_rows(&rows));
+    assert_eq!((solved.part1, solved.part2), (by_rows.part1, by_rows.part2));
+
+    let mut group = c.benchmark_group("2024 day04 solve");
+    group.bench_function("flat buffer", |b| b.iter(|| SolverDay04::solve(Box::new(input.lines())).unwrap()));
+    group.bench_function("row lookups", |b| b.iter(|| solve_by_rows(&rows)));
+    group.finish();
+}
+
+criterion_group!(benches, solve);
+criterion_main!(benches);
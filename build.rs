@@ -0,0 +1,27 @@
+/**
+ * Generates the C header for the functions in src/capi.rs into OUT_DIR, when building with the
+ * capi feature. The copy in include/ is only changed by hand, and tests/capi.rs checks it matches
+ */
+fn main()
+{
+    #[cfg(feature = "capi")]
+    {
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+        let mut header = Vec::new();
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(format!("{}/src/capi.rs", crate_dir))
+            .generate()
+            .expect("Unable to generate the C header")
+            .write(&mut header);
+
+        // cbindgen keeps the stars that start each line of a /** */ comment, so C would get two
+        let header = String::from_utf8(header).unwrap().replace("\n * *", "\n *");
+        let path = format!("{}/advent_of_code.h", std::env::var("OUT_DIR").unwrap());
+        std::fs::write(path, header).unwrap();
+    }
+}
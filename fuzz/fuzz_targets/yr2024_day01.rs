@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::solutions::yr2024::day01::SolverDay01;
+use advent_of_code::solver::Solver;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Errors are what bad input should give, so only a panic counts as a failure
+    let text = String::from_utf8_lossy(data);
+    let _ = SolverDay01::solve(Box::new(text.lines()));
+});
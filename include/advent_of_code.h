@@ -0,0 +1,74 @@
+/* Generated from src/capi.rs by cbindgen when building with --features capi. Don't edit */
+
+#ifndef ADVENT_OF_CODE_H
+#define ADVENT_OF_CODE_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define AOC_API_VERSION 1
+
+typedef enum AocStatus {
+  AOC_STATUS_OK = 0,
+  AOC_STATUS_NOT_STARTED = 1,
+  AOC_STATUS_ERROR = 2,
+  AOC_STATUS_INVALID_ARGUMENT = 3,
+} AocStatus;
+
+/**
+ * Filled in by aoc_solve. The error message belongs to the library, so it must be given back with
+ * aoc_free_result, and is null when there isn't one
+ */
+typedef struct AocResult {
+  enum AocStatus status;
+  int64_t part1;
+  int64_t part2;
+  char *error;
+} AocResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+uint32_t aoc_api_version(void);
+
+/**
+ * Solves a day from 'len' bytes of UTF-8 input, which need not be NUL terminated. Returns the
+ * same status as it puts in 'out'. A panicking solver is reported as an error rather than
+ * unwinding into the caller
+ *
+ * # Safety
+ * 'input' must point to 'len' readable bytes, and 'out' to an AocResult that can be written
+ */
+enum AocStatus aoc_solve(uint32_t year,
+                         uint8_t day,
+                         const uint8_t *input,
+                         size_t len,
+                         struct AocResult *out);
+
+/**
+ * Writes the numbers of the year's started days to 'days', up to 'capacity' of them, and returns
+ * how many there are in all. Calling it with a capacity of 0 finds how big 'days' needs to be
+ *
+ * # Safety
+ * 'days' must point to 'capacity' writable bytes, or may be null if 'capacity' is 0
+ */
+size_t aoc_list_days(uint32_t year, uint8_t *days, size_t capacity);
+
+/**
+ * Frees the error message of a result filled in by aoc_solve. Freeing the same result twice is
+ * harmless, as the message is set back to null
+ *
+ * # Safety
+ * 'result' must be null or point to an AocResult filled in by aoc_solve
+ */
+void aoc_free_result(struct AocResult *result);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ADVENT_OF_CODE_H */
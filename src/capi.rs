@@ -0,0 +1,193 @@
+/*!
+ * C API for driving the solvers from other languages. Everything here keeps to C types, and
+ * nothing already in use is changed, so that programs built against one version of the header
+ * keep working. New functions and statuses may be added
+ */
+
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::solutions;
+use crate::solver::NotStarted;
+
+// Goes up whenever something is added to the API
+pub const AOC_API_VERSION: u32 = 1;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AocStatus
+{
+    Ok = 0,
+    // The day has been set up but not solved yet
+    NotStarted = 1,
+    // The solver failed, or the year or day isn't there. The result's error says why
+    Error = 2,
+    // A pointer was null, or the input wasn't UTF-8
+    InvalidArgument = 3
+}
+
+/**
+ * Filled in by aoc_solve. The error message belongs to the library, so it must be given back with
+ * aoc_free_result, and is null when there isn't one
+ */
+#[repr(C)]
+pub struct AocResult
+{
+    pub status: AocStatus,
+    pub part1: i64,
+    pub part2: i64,
+    pub error: *mut c_char
+}
+
+fn error_message(message: &str) -> *mut c_char
+{
+    // Messages can't contain NUL, as they'd be cut short in C
+    CString::new(message.replace('\0', " ")).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn aoc_api_version() -> u32
+{
+    AOC_API_VERSION
+}
+
+/**
+ * Solves a day from 'len' bytes of UTF-8 input, which need not be NUL terminated. Returns the
+ * same status as it puts in 'out'. A panicking solver is reported as an error rather than
+ * unwinding into the caller
+ *
+ * # Safety
+ * 'input' must point to 'len' readable bytes, and 'out' to an AocResult that can be written
+ */
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(year: u32, day: u8, input: *const u8, len: usize, out: *mut AocResult) -> AocStatus
+{
+    if out.is_null()
+    {
+        return AocStatus::InvalidArgument;
+    }
+    let (status, part1, part2, error) = solve(year, day, input, len);
+    *out = AocResult { status, part1, part2, error };
+    status
+}
+
+unsafe fn solve(year: u32, day: u8, input: *const u8, len: usize) -> (AocStatus, i64, i64, *mut c_char)
+{
+    if input.is_null() && len > 0
+    {
+        return (AocStatus::InvalidArgument, 0, 0, error_message("input is null"));
+    }
+    let bytes = if len == 0 { &[][..] } else { std::slice::from_raw_parts(input, len) };
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return (AocStatus::InvalidArgument, 0, 0, error_message("input is not UTF-8"));
+    };
+
+    let year = year.to_string();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| solutions::solve(&year, day, Box::new(text.lines()))));
+    match result {
+        Ok(Ok(solution)) => (AocStatus::Ok, solution.part1 as i64, solution.part2 as i64, ptr::null_mut()),
+        Ok(Err(e)) if e.is::<NotStarted>() => (AocStatus::NotStarted, 0, 0, error_message(&e.to_string())),
+        Ok(Err(e)) => (AocStatus::Error, 0, 0, error_message(&e.to_string())),
+        Err(_) => (AocStatus::Error, 0, 0, error_message("the solver panicked"))
+    }
+}
+
+/**
+ * Writes the numbers of the year's started days to 'days', up to 'capacity' of them, and returns
+ * how many there are in all. Calling it with a capacity of 0 finds how big 'days' needs to be
+ *
+ * # Safety
+ * 'days' must point to 'capacity' writable bytes, or may be null if 'capacity' is 0
+ */
+#[no_mangle]
+pub unsafe extern "C" fn aoc_list_days(year: u32, days: *mut u8, capacity: usize) -> usize
+{
+    let year = year.to_string();
+    if !solutions::compiled_in(&year)
+    {
+        return 0;
+    }
+
+    let started: Vec<u8> = (1..=solutions::days(&year).unwrap())
+        .filter(|day| solutions::started(&year, *day))
+        .collect();
+
+    if !days.is_null()
+    {
+        for (index, day) in started.iter().take(capacity).enumerate()
+        {
+            *days.add(index) = *day;
+        }
+    }
+    started.len()
+}
+
+/**
+ * Frees the error message of a result filled in by aoc_solve. Freeing the same result twice is
+ * harmless, as the message is set back to null
+ *
+ * # Safety
+ * 'result' must be null or point to an AocResult filled in by aoc_solve
+ */
+#[no_mangle]
+pub unsafe extern "C" fn aoc_free_result(result: *mut AocResult)
+{
+    if let Some(result) = result.as_mut()
+    {
+        if !result.error.is_null()
+        {
+            drop(CString::from_raw(result.error));
+            result.error = ptr::null_mut();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use std::ffi::CStr;
+
+    use super::*;
+
+    fn solve_text(year: u32, day: u8, text: &str) -> AocResult
+    {
+        let mut result = AocResult { status: AocStatus::Ok, part1: 0, part2: 0, error: ptr::null_mut() };
+        unsafe { aoc_solve(year, day, text.as_ptr(), text.len(), &mut result) };
+        result
+    }
+
+    #[test]
+    fn test_invalid_arguments()
+    {
+        unsafe {
+            assert_eq!(aoc_solve(2024, 1, ptr::null(), 0, ptr::null_mut()), AocStatus::InvalidArgument);
+
+            let mut result = solve_text(2024, 1, "");
+            assert_eq!(aoc_solve(2024, 1, ptr::null(), 3, &mut result), AocStatus::InvalidArgument);
+            assert_eq!(CStr::from_ptr(result.error).to_str().unwrap(), "input is null");
+            aoc_free_result(&mut result);
+            assert!(result.error.is_null());
+            aoc_free_result(&mut result);
+            aoc_free_result(ptr::null_mut());
+        }
+    }
+
+    #[cfg(all(feature = "y2024", feature = "y2025"))]
+    #[test]
+    fn test_solve()
+    {
+        let result = solve_text(2024, 1, "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n");
+        assert_eq!((result.status, result.part1, result.part2), (AocStatus::Ok, 11, 31));
+        assert!(result.error.is_null());
+
+        let mut result = solve_text(2025, 1, "");
+        assert_eq!(result.status, AocStatus::NotStarted);
+        unsafe { aoc_free_result(&mut result) };
+
+        let mut days = [0; 3];
+        assert_eq!(unsafe { aoc_list_days(2024, days.as_mut_ptr(), days.len()) }, 6);
+        assert_eq!(days, [1, 2, 3]);
+        assert_eq!(unsafe { aoc_list_days(1999, ptr::null_mut(), 0) }, 0);
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
+
+/**
+ * Whitespace separated columns of numbers, read a row at a time so they can come straight from
+ * a file. Rows with the wrong number of columns, or anything that isn't a number, are left out
+ * and their line numbers kept. Blank lines are skipped without counting as malformed
+ */
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Columns
+{
+    columns: Vec<Vec<isize>>,
+    malformed: Vec<usize>,
+    line_number: usize
+}
+
+impl Columns
+{
+    pub fn new(count: usize) -> Self
+    {
+        Self { columns: vec![Vec::new(); count], ..Default::default() }
+    }
+
+    pub fn from_lines<'a>(count: usize, lines: impl IntoIterator<Item = &'a str>) -> Self
+    {
+        let mut columns = Self::new(count);
+        lines.into_iter().for_each(|line| columns.push_line(line));
+        columns
+    }
+
+    // Reads one line at a time into the same buffer, rather than holding the whole input
+    pub fn from_reader(count: usize, mut reader: impl BufRead) -> anyhow::Result<Self>
+    {
+        let mut columns = Self::new(count);
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0
+        {
+            columns.push_line(&line);
+            line.clear();
+        }
+        Ok(columns)
+    }
+
+    pub fn push_line(&mut self, line: &str)
+    {
+        self.line_number += 1;
+        if line.trim().is_empty()
+        {
+            return;
+        }
+
+        let values: Option<Vec<isize>> = line.split_whitespace().map(|value| value.parse().ok()).collect();
+        match values {
+            Some(values) if values.len() == self.columns.len() => {
+                self.columns.iter_mut().zip(values).for_each(|(column, value)| column.push(value));
+            },
+            _ => self.malformed.push(self.line_number)
+        }
+    }
+
+    pub fn count(&self) -> usize
+    {
+        self.columns.len()
+    }
+
+    pub fn rows(&self) -> usize
+    {
+        self.columns.first().map_or(0, |column| column.len())
+    }
+
+    pub fn column(&self, index: usize) -> &[isize]
+    {
+        &self.columns[index]
+    }
+
+    // Line numbers, counting from 1, of the rows that were left out
+    pub fn malformed(&self) -> &[usize]
+    {
+        &self.malformed
+    }
+
+    pub fn sorted(&self, index: usize) -> Vec<isize>
+    {
+        let mut column = self.columns[index].clone();
+        column.sort();
+        column
+    }
+
+    // The smallest of one column paired with the smallest of the other, and so on
+    pub fn sorted_pairs(&self, a: usize, b: usize) -> impl Iterator<Item = (isize, isize)>
+    {
+        self.sorted(a).into_iter().zip(self.sorted(b))
+    }
+
+    // Total distance between the sorted pairs of two columns, or None if it's too big for an isize
+    pub fn distance(&self, a: usize, b: usize) -> Option<isize>
+    {
+        self.sorted_pairs(a, b).try_fold(0isize, |total, (va, vb)| total.checked_add(va.checked_sub(vb)?.checked_abs()?))
+    }
+
+    pub fn frequencies(&self, index: usize) -> HashMap<isize, isize>
+    {
+        let mut counter = HashMap::new();
+        for value in self.columns[index].iter()
+        {
+            *counter.entry(*value).or_insert(0) += 1;
+        }
+        counter
+    }
+
+    // Each value in the first column, multiplied by how often it appears in the second, or None if that overflows
+    pub fn similarity(&self, a: usize, b: usize) -> Option<isize>
+    {
+        let counter = self.frequencies(b);
+        self.columns[a].iter().try_fold(0isize, |total, value| total.checked_add(value.checked_mul(*counter.get(value).unwrap_or(&0))?))
+    }
+}
+
+fn or_overflows(value: Option<isize>) -> String
+{
+    value.map_or("overflows".to_string(), |value| value.to_string())
+}
+
+// A summary of each column, then how each neighbouring pair of columns compares
+impl fmt::Display for Columns
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        writeln!(f, "{} rows of {} columns", self.rows(), self.count())?;
+        for index in 0..self.count()
+        {
+            let column = self.column(index);
+            let min = column.iter().min().map_or("-".to_string(), isize::to_string);
+            let max = column.iter().max().map_or("-".to_string(), isize::to_string);
+            let sum = or_overflows(column.iter().try_fold(0isize, |sum, value| sum.checked_add(*value)));
+            writeln!(f, "column {}: min {} max {} sum {} distinct {}", index + 1, min, max, sum, self.frequencies(index).len())?;
+        }
+        for index in 1..self.count()
+        {
+            writeln!(f, "columns {} and {}: distance {} similarity {}", index, index + 1, or_overflows(self.distance(index - 1, index)), or_overflows(self.similarity(index - 1, index)))?;
+        }
+
+        match self.malformed.is_empty() {
+            true => write!(f, "no malformed rows"),
+            false => write!(f, "malformed rows on lines {:?}", self.malformed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    const SAMPLE: &str = "3   4
+4   3
+
+2   5
+1   3 7
+3   9
+three 3
+3   3
+";
+
+    #[test]
+    fn test_from_lines()
+    {
+        let columns = Columns::from_lines(2, SAMPLE.lines());
+
+        assert_eq!(columns.rows(), 5);
+        assert_eq!(columns.column(0), &[3, 4, 2, 3, 3]);
+        assert_eq!(columns.malformed(), &[5, 7]);
+        assert_eq!(columns.sorted_pairs(0, 1).collect::<Vec<_>>(), vec![(2, 3), (3, 3), (3, 4), (3, 5), (4, 9)]);
+        assert_eq!(columns.distance(0, 1), Some(9));
+        assert_eq!(columns.frequencies(1)[&3], 2);
+        assert_eq!(columns.similarity(0, 1), Some(22));
+    }
+
+    #[test]
+    fn test_overflow()
+    {
+        let columns = Columns::from_lines(2, ["-9223372036854775808 9223372036854775807", "3 3"]);
+
+        assert_eq!(columns.distance(0, 1), None);
+        assert_eq!(columns.similarity(0, 1), Some(3));
+        assert_eq!(Columns::from_lines(2, ["9223372036854775807 9223372036854775807", "1 9223372036854775807"]).similarity(0, 1), None);
+        assert!(columns.to_string().contains("columns 1 and 2: distance overflows similarity 3"));
+    }
+
+    #[test]
+    fn test_from_reader()
+    {
+        let columns = Columns::from_reader(2, std::io::Cursor::new(SAMPLE)).unwrap();
+        assert_eq!(columns, Columns::from_lines(2, SAMPLE.lines()));
+    }
+
+    #[test]
+    fn test_three_columns()
+    {
+        let columns = Columns::from_lines(3, ["1 2 3", "4 5 6", "7 8"]);
+
+        assert_eq!(columns.count(), 3);
+        assert_eq!(columns.column(2), &[3, 6]);
+        assert_eq!(columns.malformed(), &[3]);
+        assert_eq!(columns.to_string().lines().last(), Some("malformed rows on lines [3]"));
+    }
+}
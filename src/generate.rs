@@ -0,0 +1,193 @@
+/*!
+ * Seeded generators for puzzle inputs, for stress testing solvers on inputs bigger and nastier
+ * than our own. Each day's generator lives with its solver, as Solver::generate, and reads its
+ * size knobs from GenerateOptions
+ */
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+use crate::solutions;
+
+/**
+ * xorshift64*, which is plenty for making inputs and means the same seed gives the same input on
+ * every machine. The seed goes through splitmix64 first, so small and zero seeds still start well
+ */
+#[derive(Clone, Debug)]
+pub struct Random(u64);
+
+impl Random
+{
+    pub fn new(seed: u64) -> Self
+    {
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self((z ^ (z >> 31)).max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64
+    {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // A number from 0 up to but not including 'bound', which must not be 0
+    pub fn below(&mut self, bound: usize) -> usize
+    {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    // A number from 'low' to 'high' inclusive
+    pub fn between(&mut self, low: isize, high: isize) -> isize
+    {
+        low + self.below((high - low) as usize + 1) as isize
+    }
+
+    // True with the given probability
+    pub fn chance(&mut self, probability: f64) -> bool
+    {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T
+    {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T])
+    {
+        for i in (1..items.len()).rev()
+        {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/**
+ * The seed, and named size knobs given as 'name=value'. Generators ask for each knob with a
+ * default, and any knob given that no generator asked for is reported as a mistake
+ */
+pub struct GenerateOptions
+{
+    pub seed: u64,
+    knobs: HashMap<String, String>,
+    asked: RefCell<BTreeSet<String>>
+}
+
+impl Default for GenerateOptions
+{
+    fn default() -> Self
+    {
+        Self { seed: 2024, knobs: HashMap::new(), asked: RefCell::new(BTreeSet::new()) }
+    }
+}
+
+impl GenerateOptions
+{
+    pub fn new(seed: u64) -> Self
+    {
+        Self { seed, ..Self::default() }
+    }
+
+    // Applies comma separated 'name=value' knobs, as in 'rows=5000,unsafe=0.3'
+    pub fn with_knobs(mut self, spec: &str) -> anyhow::Result<Self>
+    {
+        for entry in spec.split(',').filter(|entry| !entry.is_empty())
+        {
+            let (name, value) = entry.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Size knob '{}' should be 'name=value'", entry))?;
+            self.knobs.insert(name.trim().to_string(), value.trim().to_string());
+        }
+        Ok(self)
+    }
+
+    pub fn random(&self) -> Random
+    {
+        Random::new(self.seed)
+    }
+
+    pub fn knob<T: FromStr>(&self, name: &str, default: T) -> anyhow::Result<T>
+    {
+        self.asked.borrow_mut().insert(name.to_string());
+        match self.knobs.get(name) {
+            None => Ok(default),
+            Some(value) => value.parse().map_err(|_| anyhow::anyhow!("'{}' isn't a valid value for {}", value, name))
+        }
+    }
+
+    // A knob that is a proportion, from 0 to 1
+    pub fn ratio(&self, name: &str, default: f64) -> anyhow::Result<f64>
+    {
+        let ratio = self.knob(name, default)?;
+        if !(0.0..=1.0).contains(&ratio)
+        {
+            anyhow::bail!("{} must be between 0 and 1", name);
+        }
+        Ok(ratio)
+    }
+
+    fn unknown_knobs(&self) -> Vec<&str>
+    {
+        let asked = self.asked.borrow();
+        let mut unknown: Vec<&str> = self.knobs.keys().filter(|name| !asked.contains(*name)).map(|name| name.as_str()).collect();
+        unknown.sort();
+        unknown
+    }
+}
+
+// An input for the day, as the puzzle would give it
+pub fn generate(year: &str, day_number: u8, options: &GenerateOptions) -> anyhow::Result<String>
+{
+    let input = solutions::generate(year, day_number, options)?;
+
+    let unknown = options.unknown_knobs();
+    if !unknown.is_empty()
+    {
+        let known: Vec<String> = options.asked.borrow().iter().cloned().collect();
+        anyhow::bail!("Unknown size knobs {} for {} day {}, which takes {}", unknown.join(", "), year, day_number, known.join(", "));
+    }
+    Ok(input)
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn test_random()
+    {
+        let mut random = Random::new(0);
+        let numbers: Vec<isize> = (0..1000).map(|_| random.between(-3, 3)).collect();
+        assert!((-3..=3).all(|n| numbers.contains(&n)));
+        assert!(numbers.iter().all(|n| (-3..=3).contains(n)));
+
+        assert_eq!(Random::new(7).next_u64(), Random::new(7).next_u64());
+        assert_ne!(Random::new(7).next_u64(), Random::new(8).next_u64());
+
+        let mut items: Vec<usize> = (0..10).collect();
+        random.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_knobs()
+    {
+        let options = GenerateOptions::new(1).with_knobs("rows=20,unsafe=0.25").unwrap();
+        assert_eq!(options.knob("rows", 1000).unwrap(), 20);
+        assert_eq!(options.knob("columns", 2).unwrap(), 2);
+        assert_eq!(options.unknown_knobs(), vec!["unsafe"]);
+        assert_eq!(options.ratio("unsafe", 0.5).unwrap(), 0.25);
+        assert!(options.unknown_knobs().is_empty());
+
+        assert!(GenerateOptions::default().with_knobs("rows").is_err());
+        let options = GenerateOptions::default().with_knobs("rows=many,unsafe=2").unwrap();
+        assert_eq!(options.knob("rows", 0).unwrap_err().to_string(), "'many' isn't a valid value for rows");
+        assert!(options.ratio("unsafe", 0.5).is_err());
+    }
+}
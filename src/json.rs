@@ -0,0 +1,59 @@
+// Just enough JSON writing for the wasm and HTTP front ends, which only ever send small objects
+
+pub(crate) fn json_string(text: &str) -> String
+{
+    let mut json = String::from("\"");
+    for c in text.chars()
+    {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c)
+        }
+    }
+    json.push('"');
+    json
+}
+
+// An object from fields whose values are already JSON
+pub(crate) fn json_object<K: AsRef<str>>(fields: impl IntoIterator<Item = (K, String)>) -> String
+{
+    let fields: Vec<String> = fields.into_iter()
+        .map(|(key, value)| format!("{}: {}", json_string(key.as_ref()), value))
+        .collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+// An array from values that are already JSON
+pub(crate) fn json_array(values: impl IntoIterator<Item = String>) -> String
+{
+    format!("[{}]", values.into_iter().collect::<Vec<String>>().join(", "))
+}
+
+pub(crate) fn json_error(message: &str) -> String
+{
+    json_object([("error", json_string(message))])
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn test_json_string()
+    {
+        assert_eq!(json_string("a \"b\"\n\\c\t"), "\"a \\\"b\\\"\\n\\\\c\\u0009\"");
+    }
+
+    #[test]
+    fn test_json_object()
+    {
+        assert_eq!(json_object([("a", "1".to_string()), ("b\"", json_string("c"))]), "{\"a\": 1, \"b\\\"\": \"c\"}");
+        assert_eq!(json_object(Vec::<(&str, String)>::new()), "{}");
+        assert_eq!(json_array([1, 2].map(|n: i32| n.to_string())), "[1, 2]");
+        assert_eq!(json_error("not started"), "{\"error\": \"not started\"}");
+    }
+}
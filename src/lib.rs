@@ -0,0 +1,27 @@
+/*!
+ * Advent of Code solutions, with everything needed to run, check and show them. The binary is a
+ * command line front end to this library
+ */
+
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod columns;
+pub mod generate;
+mod json;
+#[cfg(feature = "python")]
+mod python;
+pub mod repl;
+pub mod runner;
+pub mod server;
+pub mod solver;
+pub mod solutions;
+pub mod status;
+pub mod visualise;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "watch")]
+pub mod watch;
+#[cfg(test)]
+mod property;
+#[cfg(test)]
+mod verify;
@@ -1,33 +1,204 @@
+use std::path::PathBuf;
 use std::process::exit;
+use std::time::Duration;
 
-mod runner;
-mod solver;
-mod solutions;
-mod verify;
+use advent_of_code::generate::{generate, GenerateOptions};
+use advent_of_code::repl::repl;
+use advent_of_code::runner::{explain_day, get_solution, visualise_day};
+use advent_of_code::server::{serve, ServeOptions};
+use advent_of_code::solver::{parse_part, ExplainOptions, NotStarted};
+use advent_of_code::visualise::{playback, VisualiseOptions};
+use advent_of_code::{solutions, status};
 
-use runner::get_solution;
+#[cfg(feature = "watch")]
+fn watch_day(year : &str, day_number : u8) -> anyhow::Result<()>
+{
+    advent_of_code::watch::watch(year, day_number)
+}
+
+#[cfg(not(feature = "watch"))]
+fn watch_day(_year : &str, _day_number : u8) -> anyhow::Result<()>
+{
+    Err(anyhow::anyhow!("--watch needs the 'watch' feature"))
+}
 
 fn run_day(year : &str, day_number : u8)
 {
     match get_solution(year, day_number)
     {
         Ok(solution)  => println!("Day {:02}: {}", day_number, solution),
+        Err(e) if e.is::<NotStarted>() => println!("Day {:02}: {}", day_number, e),
         Err(e) => eprintln!("Day {:02} : {}", day_number, e)
     }
 }
 
-fn main() 
+#[derive(Default)]
+struct Args
+{
+    positional: Vec<String>,
+    visualise: bool,
+    explain: bool,
+    watch: bool,
+    playback: Option<PathBuf>,
+    visualise_options: VisualiseOptions,
+    explain_options: ExplainOptions,
+    serve_options: ServeOptions,
+    generate_options: GenerateOptions
+}
+
+fn flag_value(flag: &str, value: Option<String>) -> anyhow::Result<String>
+{
+    value.ok_or_else(|| anyhow::anyhow!("{} requires a value", flag))
+}
+
+fn parse_args() -> anyhow::Result<Args>
 {
-    let first_arg: Option<String> = std::env::args().nth(1);
-    let year_arg: Option<String> = std::env::args().nth(2);
-    
-    let year: &str = year_arg.as_deref().unwrap_or("2025");
+    let mut args = Args::default();
+    let mut raw_args = std::env::args().skip(1);
+
+    while let Some(arg) = raw_args.next()
+    {
+        match arg.as_str() {
+            "--visualise" => args.visualise = true,
+            "--playback" => args.playback = Some(PathBuf::from(flag_value(&arg, raw_args.next())?)),
+            "--paused" => args.visualise_options.paused = true,
+            "--headless" => args.visualise_options.headless = true,
+            "--explain" => args.explain = true,
+            "--watch" => args.watch = true,
+            "--plain" => args.explain_options.plain = true,
+            "--query" => args.explain_options.query = Some(flag_value(&arg, raw_args.next())?),
+            "--part" => {
+                let part = parse_part(&flag_value(&arg, raw_args.next())?)?;
+                args.visualise_options.part = part;
+                args.explain_options.part = part;
+            },
+            "--delay" => args.visualise_options.delay = Duration::from_millis(flag_value(&arg, raw_args.next())?.parse()?),
+            "--record" => args.visualise_options.record = Some(PathBuf::from(flag_value(&arg, raw_args.next())?)),
+            "--export" => args.visualise_options.export.path = Some(PathBuf::from(flag_value(&arg, raw_args.next())?)),
+            "--cell-size" => args.visualise_options.export.cell_size = flag_value(&arg, raw_args.next())?.parse()?,
+            "--palette" => {
+                let spec = flag_value(&arg, raw_args.next())?;
+                args.visualise_options.export.palette = args.visualise_options.export.palette.clone().with_overrides(&spec)?;
+            },
+            "--symbols" => args.visualise_options.export.symbols = true,
+            "--address" => args.serve_options.address = flag_value(&arg, raw_args.next())?,
+            "--workers" => args.serve_options.workers = flag_value(&arg, raw_args.next())?.parse()?,
+            "--timeout" => args.serve_options.timeout = Duration::from_secs_f64(flag_value(&arg, raw_args.next())?.parse()?),
+            "--seed" => args.generate_options.seed = flag_value(&arg, raw_args.next())?.parse()?,
+            "--size" => {
+                let spec = flag_value(&arg, raw_args.next())?;
+                args.generate_options = std::mem::take(&mut args.generate_options).with_knobs(&spec)?;
+            },
+            "--every" => args.visualise_options.export.every = flag_value(&arg, raw_args.next())?.parse()?,
+            flag if flag.starts_with("--") => anyhow::bail!("Unknown option '{}'", flag),
+            _ => args.positional.push(arg)
+        }
+    }
+
+    Ok(args)
+}
+
+fn main()
+{
+    let args = parse_args().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(1);
+    });
+
+    if let Some(path) = &args.playback
+    {
+        if let Err(e) = playback(path, &args.visualise_options)
+        {
+            eprintln!("{}", e);
+            exit(1);
+        }
+        return;
+    }
+
+    let first_arg: Option<&String> = args.positional.first();
+    let year_arg: Option<&String> = args.positional.get(1);
+
+    let year: &str = year_arg.map(|s| s.as_str()).unwrap_or("2025");
 
-    if let Some(arg) = first_arg
+    if first_arg.is_some_and(|arg| arg == "status")
     {
-        if regex::Regex::new(r"^day\d\d?$").unwrap().is_match(&arg) {
+        match status::status() {
+            Ok(status) => println!("{}", status),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1);
+            }
+        }
+    }
+    else if first_arg.is_some_and(|arg| arg == "repl")
+    {
+        // The day comes before the year here, as in 'repl day05 2024'
+        let day_arg = args.positional.get(1).and_then(|arg| arg.strip_prefix("day")).and_then(|day| day.parse::<u8>().ok());
+        let year = args.positional.get(2).map(|s| s.as_str()).unwrap_or("2025");
+        let Some(day) = day_arg.filter(|day| (1..=25).contains(day)) else {
+            eprintln!("repl needs a day, e.g. 'repl day05 2024'");
+            exit(1);
+        };
+        if let Err(e) = repl(year, day)
+        {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+    else if first_arg.is_some_and(|arg| arg == "generate")
+    {
+        // As in 'generate 2024 5' or 'generate 2024 day05'
+        let year = args.positional.get(1).map(|s| s.as_str()).unwrap_or("2025");
+        let day_arg = args.positional.get(2).map(|arg| arg.trim_start_matches("day")).and_then(|day| day.parse::<u8>().ok());
+        let Some(day) = day_arg else {
+            eprintln!("generate needs a year and day, e.g. 'generate 2024 5 --seed 7 --size reports=5000'");
+            exit(1);
+        };
+        match generate(year, day, &args.generate_options) {
+            Ok(input) => print!("{}", input),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1);
+            }
+        }
+    }
+    else if first_arg.is_some_and(|arg| arg == "serve")
+    {
+        if let Err(e) = serve(&args.serve_options)
+        {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+    else if let Some(arg) = first_arg
+    {
+        if regex::Regex::new(r"^day\d\d?$").unwrap().is_match(arg) {
             let selected_day = arg[3..].parse().unwrap();
             match selected_day {
+                1..=25 if args.visualise => {
+                    if let Err(e) = visualise_day(year, selected_day, &args.visualise_options)
+                    {
+                        eprintln!("Day {:02} : {}", selected_day, e);
+                        exit(1);
+                    }
+                },
+                1..=25 if args.explain => {
+                    match explain_day(year, selected_day, &args.explain_options)
+                    {
+                        Ok(explanation) => println!("{}", explanation),
+                        Err(e) => {
+                            eprintln!("Day {:02} : {}", selected_day, e);
+                            exit(1);
+                        }
+                    }
+                },
+                1..=25 if args.watch => {
+                    if let Err(e) = watch_day(year, selected_day)
+                    {
+                        eprintln!("Day {:02} : {}", selected_day, e);
+                        exit(1);
+                    }
+                },
                 1..=25 => run_day(year, selected_day),
                 _ => eprintln!("Invalid day, must be 1-25"),
             }
@@ -38,11 +209,25 @@ fn main()
             exit(1);
         }
     }
+    else if args.visualise || args.explain || args.watch
+    {
+        eprintln!("--visualise, --explain and --watch need a day, e.g. 'day06 2024 --visualise'");
+        exit(1);
+    }
+    else if !solutions::compiled_in(year)
+    {
+        eprintln!("{}", solutions::not_compiled_in(year));
+        exit(1);
+    }
     else
     {
-        for i in 1..26
-        {
-            run_day(year, i);
+        // With nothing asked for, the default year is shown as a calendar, as status shows every year
+        match status::year_calendar(year, solutions::days(year).unwrap()) {
+            Ok(calendar) => println!("{}\n{}", calendar, status::LEGEND),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1);
+            }
         }
     }
 }
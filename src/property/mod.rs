@@ -0,0 +1,239 @@
+/*!
+ * Property tests comparing solvers with simple brute-force references. Inputs come from each
+ * day's generator, and when the two disagree the input is shrunk to the smallest one that still
+ * shows it, which is what the failure reports
+ */
+
+#[cfg(feature = "y2024")]
+mod yr2024;
+
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::generate::GenerateOptions;
+use crate::solver::{Solution, Solver};
+
+/**
+ * A brute-force answer to a day, or None if the input is one the reference doesn't judge (an
+ * update with no unique order, say). Skipped inputs count as passing, which also keeps shrinking
+ * to inputs where the reference knows the answer
+ */
+pub type Reference = fn(&[&str]) -> Option<Solution>;
+
+// The solver's answers, or what went wrong, in a form that can be compared and shown
+fn run_solver<S: Solver>(input: &str) -> Result<(isize, isize), String>
+{
+    match panic::catch_unwind(AssertUnwindSafe(|| S::solve(Box::new(input.lines())))) {
+        Ok(Ok(solution)) => Ok((solution.part1, solution.part2)),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("panicked".to_string())
+    }
+}
+
+// Where the solver and reference disagree, both answers
+fn disagreement<S: Solver>(reference: Reference, input: &str) -> Option<String>
+{
+    // The reference sees the lines as the solver would, without blank lines or padding
+    let lines: Vec<&str> = S::clean(Box::new(input.lines()));
+    let expected = reference(&lines)?;
+    let expected = (expected.part1, expected.part2);
+
+    match run_solver::<S>(input) {
+        Ok(actual) if actual == expected => None,
+        Ok(actual) => Some(format!("solver gave {:?}, reference gave {:?}", actual, expected)),
+        Err(e) => Some(format!("solver failed with '{}', reference gave {:?}", e, expected))
+    }
+}
+
+// Numbers in the text, as (start, end) byte ranges
+fn numbers(line: &str) -> Vec<(usize, usize)>
+{
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (index, c) in line.char_indices().chain([(line.len(), ' ')])
+    {
+        match (c.is_ascii_digit(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(from)) => {
+                ranges.push((from, index));
+                start = None;
+            },
+            _ => ()
+        }
+    }
+    ranges
+}
+
+// Smaller versions of a number, smallest first
+fn smaller_numbers(number: &str) -> Vec<String>
+{
+    let Ok(value) = number.parse::<u64>() else {
+        return Vec::new();
+    };
+    let mut smaller: Vec<u64> = vec![0, 1, value / 2, value - value.min(1)];
+    smaller.retain(|candidate| *candidate < value);
+    smaller.dedup();
+    smaller.iter().map(|candidate| candidate.to_string()).collect()
+}
+
+/**
+ * The smallest input found that still fails. Whole runs of lines are removed first, halving the
+ * run each time nothing can go, then single characters, then numbers are made smaller. Every pass
+ * is repeated until none of them make progress
+ */
+pub fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String
+{
+    let mut lines: Vec<String> = input.lines().map(|line| line.to_string()).collect();
+    let joined = |lines: &[String]| lines.join("\n");
+
+    loop
+    {
+        let before = lines.clone();
+
+        let mut run = lines.len().max(1);
+        while run > 0
+        {
+            let mut start = 0;
+            while start < lines.len()
+            {
+                let mut candidate = lines.clone();
+                candidate.drain(start..(start + run).min(lines.len()));
+                if fails(&joined(&candidate))
+                {
+                    lines = candidate;
+                }
+                else
+                {
+                    start += run;
+                }
+            }
+            run /= 2;
+        }
+
+        for row in 0..lines.len()
+        {
+            let mut index = 0;
+            while index < lines[row].len()
+            {
+                let mut candidate = lines.clone();
+                candidate[row].remove(index);
+                if fails(&joined(&candidate))
+                {
+                    lines = candidate;
+                }
+                else
+                {
+                    index += lines[row][index..].chars().next().unwrap().len_utf8();
+                }
+            }
+        }
+
+        for row in 0..lines.len()
+        {
+            // Working from the end means changing a number doesn't move the ones still to do
+            for (start, end) in numbers(&lines[row]).into_iter().rev()
+            {
+                for smaller in smaller_numbers(&lines[row][start..end])
+                {
+                    let mut candidate = lines.clone();
+                    candidate[row].replace_range(start..end, &smaller);
+                    if fails(&joined(&candidate))
+                    {
+                        lines = candidate;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if lines == before
+        {
+            return joined(&lines);
+        }
+    }
+}
+
+/**
+ * Runs the solver and the reference on 'cases' generated inputs, one per seed, with the given
+ * size knobs. Panics with the seed and the shrunk input of the first case where they disagree
+ */
+pub fn check<S: Solver>(reference: Reference, knobs: &str, cases: u64)
+{
+    for seed in 0..cases
+    {
+        let options = GenerateOptions::new(seed).with_knobs(knobs).unwrap();
+        let input = S::generate(&options).unwrap();
+        if disagreement::<S>(reference, &input).is_none()
+        {
+            continue;
+        }
+
+        let shrunk = shrink(&input, |candidate| disagreement::<S>(reference, candidate).is_some());
+
+        panic!("Seed {} with '{}' fails, shrunk from {} to {} lines:\n{}\n{}",
+            seed, knobs, input.lines().count(), shrunk.lines().count(), shrunk, disagreement::<S>(reference, &shrunk).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::generate::Random;
+    use crate::solver::SolutionResult;
+
+    // Sums the numbers in its input, but forgets any over 50
+    struct ForgetfulSum {}
+
+    impl Solver for ForgetfulSum
+    {
+        fn solve_impl(lines: Vec<&str>) -> SolutionResult
+        {
+            let numbers: Vec<isize> = lines.iter().flat_map(|line| line.split(' ')).map(|n| n.parse().unwrap()).collect();
+            Ok(Solution { part1: numbers.iter().filter(|n| **n <= 50).sum(), part2: 0 })
+        }
+
+        fn generate(options: &GenerateOptions) -> anyhow::Result<String>
+        {
+            let mut random: Random = options.random();
+            let lines: usize = options.knob("lines", 10)?;
+            Ok((0..lines).map(|_| format!("{} {}\n", random.between(0, 60), random.between(0, 60))).collect())
+        }
+    }
+
+    fn sum(lines: &[&str]) -> Option<Solution>
+    {
+        let numbers: Option<Vec<isize>> = lines.iter().flat_map(|line| line.split(' ')).map(|n| n.parse().ok()).collect();
+        Some(Solution { part1: numbers?.iter().sum(), part2: 0 })
+    }
+
+    #[test]
+    fn test_numbers()
+    {
+        assert_eq!(numbers("mul(12,345)x7"), vec![(4, 6), (7, 10), (12, 13)]);
+        assert_eq!(smaller_numbers("40"), vec!["0", "1", "20", "39"]);
+        assert_eq!(smaller_numbers("1"), vec!["0"]);
+        assert!(smaller_numbers("0").is_empty());
+    }
+
+    #[test]
+    fn test_shrink()
+    {
+        // One number over 50 is all it takes, and 51 is the smallest of those
+        let shrunk = shrink("3 58\n12 4\n60 2", |input| disagreement::<ForgetfulSum>(sum, input).is_some());
+        assert_eq!(shrunk, "51");
+
+        let shrunk = shrink("a\nb\nc\nd\ne", |input| input.contains('c') && input.contains('e'));
+        assert_eq!(shrunk, "c\ne");
+    }
+
+    #[test]
+    fn test_check()
+    {
+        let failure = panic::catch_unwind(|| check::<ForgetfulSum>(sum, "lines=5", 10)).unwrap_err();
+        let message = failure.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("Seed 0 with 'lines=5' fails, shrunk from 5 to 1 lines:\n51\n"), "{}", message);
+        assert!(message.ends_with("solver gave (0, 0), reference gave (51, 0)"), "{}", message);
+
+        check::<ForgetfulSum>(sum, "lines=0", 10);
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+
+use super::check;
+use crate::solutions::yr2024::day01::SolverDay01;
+use crate::solutions::yr2024::day02::SolverDay02;
+use crate::solutions::yr2024::day03::SolverDay03;
+use crate::solutions::yr2024::day04::SolverDay04;
+use crate::solutions::yr2024::day05::SolverDay05;
+use crate::solutions::yr2024::day06::SolverDay06;
+use crate::solver::Solution;
+
+fn numbers(line: &str) -> Option<Vec<isize>>
+{
+    line.split_whitespace().map(|number| number.parse().ok()).collect()
+}
+
+// Pairs off the smallest left with the smallest right each time, and counts every match for part 2
+fn day01_reference(lines: &[&str]) -> Option<Solution>
+{
+    let rows: Vec<Vec<isize>> = lines.iter().map(|line| numbers(line)).collect::<Option<_>>()?;
+    if rows.iter().any(|row| row.len() != 2)
+    {
+        return None;
+    }
+    let mut left: Vec<isize> = rows.iter().map(|row| row[0]).collect();
+    let mut right: Vec<isize> = rows.iter().map(|row| row[1]).collect();
+
+    let part2 = left.iter().map(|l| l * right.iter().filter(|r| *r == l).count() as isize).sum();
+    let mut part1 = 0;
+    while !left.is_empty()
+    {
+        let l = left.remove(left.iter().enumerate().min_by_key(|(_, value)| **value).unwrap().0);
+        let r = right.remove(right.iter().enumerate().min_by_key(|(_, value)| **value).unwrap().0);
+        part1 += (l - r).abs();
+    }
+    Some(Solution { part1, part2 })
+}
+
+#[test]
+fn day01()
+{
+    check::<SolverDay01>(day01_reference, "rows=30,digits=2", 300);
+}
+
+// Tries every report as it is, then with each level taken out
+fn day02_reference(lines: &[&str]) -> Option<Solution>
+{
+    let safe = |levels: &[isize]| {
+        levels.windows(2).all(|pair| (1..=3).contains(&(pair[1] - pair[0])))
+            || levels.windows(2).all(|pair| (1..=3).contains(&(pair[0] - pair[1])))
+    };
+
+    let mut solution = Solution::default();
+    for line in lines
+    {
+        let levels = numbers(line)?;
+        solution.part1 += safe(&levels) as isize;
+        solution.part2 += (0..levels.len()).any(|index| safe(&[&levels[..index], &levels[index + 1..]].concat())) as isize;
+    }
+    Some(solution)
+}
+
+#[test]
+fn day02()
+{
+    check::<SolverDay02>(day02_reference, "reports=20,min_levels=3,max_levels=9", 500);
+}
+
+// The puzzle's instructions as a regex, rather than the solver's tokenizer
+fn day03_reference(lines: &[&str]) -> Option<Solution>
+{
+    let instruction = Regex::new(r"mul\(([0-9]{1,3}),([0-9]{1,3})\)|do\(\)|don't\(\)").unwrap();
+
+    let mut solution = Solution::default();
+    let mut enabled = true;
+    for captures in instruction.captures_iter(&lines.join(""))
+    {
+        match &captures[0] {
+            "do()" => enabled = true,
+            "don't()" => enabled = false,
+            _ => {
+                let product = captures[1].parse::<isize>().unwrap() * captures[2].parse::<isize>().unwrap();
+                solution.part1 += product;
+                solution.part2 += if enabled { product } else { 0 };
+            }
+        }
+    }
+    Some(solution)
+}
+
+#[test]
+fn day03()
+{
+    check::<SolverDay03>(day03_reference, "lines=3,length=60,muls=0.2,switches=0.1,decoys=0.3", 300);
+}
+
+// Reads XMAS from every letter in every direction, and looks for MAS both ways across every A
+fn day04_reference(lines: &[&str]) -> Option<Solution>
+{
+    let grid: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+    let at = |x: isize, y: isize| -> Option<char> {
+        grid.get(usize::try_from(y).ok()?)?.get(usize::try_from(x).ok()?).copied()
+    };
+
+    let mut solution = Solution::default();
+    for (y, row) in grid.iter().enumerate()
+    {
+        for x in 0..row.len()
+        {
+            let (x, y) = (x as isize, y as isize);
+            for (dx, dy) in [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)]
+            {
+                let word: Option<String> = (0..4).map(|step| at(x + dx * step, y + dy * step)).collect();
+                solution.part1 += (word.as_deref() == Some("XMAS")) as isize;
+            }
+
+            let diagonal = |dx: isize| -> Option<String> {
+                [at(x - dx, y - 1), at(x, y), at(x + dx, y + 1)].into_iter().collect()
+            };
+            let is_mas = |word: Option<String>| matches!(word.as_deref(), Some("MAS") | Some("SAM"));
+            solution.part2 += (is_mas(diagonal(1)) && is_mas(diagonal(-1))) as isize;
+        }
+    }
+    Some(solution)
+}
+
+#[test]
+fn day04()
+{
+    check::<SolverDay04>(day04_reference, "width=8,height=6,words=2,crosses=1", 300);
+}
+
+/**
+ * Updates that break no rule as they are count for part 1. Others are sorted with the rules as a
+ * comparison, and only judged if the sorted order keeps to every rule and has a rule between each
+ * neighbouring pair, which is when the order is unique
+ */
+fn day05_reference(lines: &[&str]) -> Option<Solution>
+{
+    let mut rules = HashSet::new();
+    let mut solution = Solution::default();
+
+    for line in lines
+    {
+        if let Some((first, second)) = line.split_once('|')
+        {
+            rules.insert((first.trim().parse::<isize>().ok()?, second.trim().parse::<isize>().ok()?));
+            continue;
+        }
+
+        let update: Vec<isize> = line.split(',').map(|page| page.trim().parse().ok()).collect::<Option<_>>()?;
+        let repeats = (0..update.len()).any(|i| update[i + 1..].contains(&update[i]));
+        if update.is_empty() || repeats
+        {
+            return None;
+        }
+        let in_order = (0..update.len()).all(|i| (i + 1..update.len()).all(|j| !rules.contains(&(update[j], update[i]))));
+        if in_order
+        {
+            solution.part1 += update[update.len() / 2];
+            continue;
+        }
+
+        let mut sorted = update.clone();
+        sorted.sort_by(|a, b| match (rules.contains(&(*a, *b)), rules.contains(&(*b, *a))) {
+            (true, _) => std::cmp::Ordering::Less,
+            (_, true) => std::cmp::Ordering::Greater,
+            _ => std::cmp::Ordering::Equal
+        });
+
+        let keeps_rules = (0..sorted.len()).all(|i| (i + 1..sorted.len()).all(|j| !rules.contains(&(sorted[j], sorted[i]))));
+        let unique = sorted.windows(2).all(|pair| rules.contains(&(pair[0], pair[1])));
+        if !keeps_rules || !unique
+        {
+            return None;
+        }
+        solution.part2 += sorted[sorted.len() / 2];
+    }
+    Some(solution)
+}
+
+#[test]
+fn day05()
+{
+    check::<SolverDay05>(day05_reference, "pages=7,updates=6,min_length=1,max_length=7", 300);
+}
+
+// Where the guard walks from the start, and whether it ends up walking in a loop
+fn walk(grid: &[Vec<char>], start: (isize, isize)) -> (HashSet<(isize, isize)>, bool)
+{
+    let (mut x, mut y) = start;
+    let (mut dx, mut dy) = (0, -1);
+    let mut visited = HashSet::new();
+    let mut states = HashSet::new();
+
+    loop
+    {
+        visited.insert((x, y));
+        if !states.insert((x, y, dx, dy))
+        {
+            return (visited, true);
+        }
+        let (ahead_x, ahead_y) = (x + dx, y + dy);
+        if ahead_x < 0 || ahead_y < 0 || ahead_y as usize >= grid.len() || ahead_x as usize >= grid[0].len()
+        {
+            return (visited, false);
+        }
+        if grid[ahead_y as usize][ahead_x as usize] == '#'
+        {
+            (dx, dy) = (-dy, dx);
+        }
+        else
+        {
+            (x, y) = (ahead_x, ahead_y);
+        }
+    }
+}
+
+// Walks the map, then walks it again with an obstacle added on every other clear tile
+fn day06_reference(lines: &[&str]) -> Option<Solution>
+{
+    let mut grid: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+    let guards: Vec<(isize, isize)> = grid.iter().enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().filter(|(_, tile)| **tile == '^').map(move |(x, _)| (x as isize, y as isize)))
+        .collect();
+    if guards.len() != 1 || grid.iter().any(|row| row.len() != grid[0].len())
+    {
+        return None;
+    }
+    let start = guards[0];
+
+    // A guard that never leaves has no answer to give
+    let (visited, loops) = walk(&grid, start);
+    if loops
+    {
+        return None;
+    }
+    let part1 = visited.len() as isize;
+    let mut part2 = 0;
+    for y in 0..grid.len()
+    {
+        for x in 0..grid[0].len()
+        {
+            if grid[y][x] == '.'
+            {
+                grid[y][x] = '#';
+                part2 += walk(&grid, start).1 as isize;
+                grid[y][x] = '.';
+            }
+        }
+    }
+    Some(Solution { part1, part2 })
+}
+
+#[test]
+fn day06()
+{
+    check::<SolverDay06>(day06_reference, "width=9,height=7,obstacles=0.15", 300);
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use pyo3::exceptions::{PyIndexError, PyNotImplementedError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::columns::Columns;
+use crate::solutions;
+use crate::solver::NotStarted;
+use crate::visualise::{Colour, Grid};
+
+// The answers to both parts, and how long the solver took to find them
+#[pyclass(name = "Solution", frozen, get_all)]
+struct PySolution
+{
+    part1: isize,
+    part2: isize,
+    seconds: f64
+}
+
+#[pymethods]
+impl PySolution
+{
+    fn __repr__(&self) -> String
+    {
+        format!("Solution(part1={}, part2={}, seconds={:.6})", self.part1, self.part2, self.seconds)
+    }
+}
+
+/**
+ * Solves a day from the text of its input. Days that haven't been started raise
+ * NotImplementedError, and solvers that fail raise ValueError
+ */
+#[pyfunction]
+fn solve(py: Python<'_>, year: &str, day: u8, text: &str) -> PyResult<PySolution>
+{
+    let start = Instant::now();
+    let result = py.allow_threads(|| solutions::solve(year, day, Box::new(text.lines())));
+    let seconds = start.elapsed().as_secs_f64();
+
+    match result {
+        Ok(solution) => Ok(PySolution { part1: solution.part1, part2: solution.part2, seconds }),
+        Err(e) if e.is::<NotStarted>() => Err(PyNotImplementedError::new_err(format!("{} day {} not started", year, day))),
+        Err(e) => Err(PyValueError::new_err(e.to_string()))
+    }
+}
+
+// Each year that was compiled in, with the number of days it has
+#[pyfunction]
+fn years() -> HashMap<String, u8>
+{
+    solutions::YEARS.iter()
+        .filter(|year| solutions::compiled_in(year))
+        .map(|year| (year.to_string(), solutions::days(year).unwrap()))
+        .collect()
+}
+
+/**
+ * Parses whitespace separated columns of numbers, as in 2024 day 1. Returns the columns and the
+ * line numbers of any rows that didn't have the right number of values
+ */
+#[pyfunction]
+fn columns(text: &str, count: usize) -> (Vec<Vec<isize>>, Vec<usize>)
+{
+    let columns = Columns::from_lines(count, text.lines());
+    ((0..count).map(|index| columns.column(index).to_vec()).collect(), columns.malformed().to_vec())
+}
+
+// A grid of characters made from text, which can be coloured and printed in a terminal
+#[pyclass(name = "Grid")]
+struct PyGrid
+{
+    grid: Grid
+}
+
+#[pymethods]
+impl PyGrid
+{
+    #[new]
+    fn new(text: &str) -> Self
+    {
+        Self { grid: Grid::from_display(&text) }
+    }
+
+    #[getter]
+    fn width(&self) -> usize
+    {
+        self.grid.width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize
+    {
+        self.grid.height()
+    }
+
+    fn get(&self, x: usize, y: usize) -> PyResult<char>
+    {
+        self.grid.rows().get(y).and_then(|row| row.get(x))
+            .map(|cell| cell.symbol)
+            .ok_or_else(|| PyIndexError::new_err(format!("({}, {}) is outside the grid", x, y)))
+    }
+
+    fn set_colour(&mut self, x: usize, y: usize, colour: &str) -> PyResult<()>
+    {
+        let colour = Colour::from_name(colour).ok_or_else(|| PyValueError::new_err(format!("Unknown colour '{}'", colour)))?;
+        self.grid.set_colour(x, y, colour);
+        Ok(())
+    }
+
+    fn rows(&self) -> Vec<String>
+    {
+        self.grid.rows().iter().map(|row| row.iter().map(|cell| cell.symbol).collect()).collect()
+    }
+
+    // Coloured cells are wrapped in ANSI escapes, which notebooks show as colours
+    fn __str__(&self) -> String
+    {
+        self.grid.to_string()
+    }
+}
+
+#[pymodule]
+fn advent_of_code(module: &Bound<'_, PyModule>) -> PyResult<()>
+{
+    module.add_function(wrap_pyfunction!(solve, module)?)?;
+    module.add_function(wrap_pyfunction!(years, module)?)?;
+    module.add_function(wrap_pyfunction!(columns, module)?)?;
+    module.add_class::<PySolution>()?;
+    module.add_class::<PyGrid>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test
+{
+    use std::ffi::CString;
+
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    // Runs Python code against the module, as a notebook would use it
+    fn run(code: &str)
+    {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "advent_of_code").unwrap();
+            advent_of_code(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("aoc", module).unwrap();
+            py.run(&CString::new(code).unwrap(), Some(&globals), None).unwrap();
+        });
+    }
+
+    #[cfg(all(feature = "y2024", feature = "y2025"))]
+    #[test]
+    fn test_solve()
+    {
+        run("
+solution = aoc.solve('2024', 1, '3   4\\n4   3\\n2   5\\n1   3\\n3   9\\n3   3\\n')
+assert (solution.part1, solution.part2) == (11, 31)
+assert solution.seconds >= 0
+assert aoc.years() == {'2024': 25, '2025': 12}
+
+try:
+    aoc.solve('2025', 1, '')
+    assert False
+except NotImplementedError:
+    pass
+");
+    }
+
+    #[test]
+    fn test_helpers()
+    {
+        run("
+assert aoc.columns('1 2\\n3\\n4 5', 2) == ([[1, 4], [2, 5]], [2])
+
+grid = aoc.Grid('ab\\ncd')
+grid.set_colour(1, 1, 'green')
+assert (grid.width, grid.height, grid.get(1, 0)) == (2, 2, 'b')
+assert grid.rows() == ['ab', 'cd']
+assert str(grid) == 'ab\\nc\\x1b[1;32md\\x1b[0m'
+");
+    }
+}
@@ -0,0 +1,286 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::runner::{input_files, input_path};
+use crate::solutions;
+use crate::solver::{parse_part, ExplainOptions};
+
+const HELP: &str = "\
+part1, part2, solve    solve the loaded input, giving one part or both
+show                   the input as the solver parsed it
+explain [part]         how the solver reached its answer
+head [lines]           the start of the loaded input, 10 lines unless told otherwise
+files                  the input and examples there are for the day
+real                   load the real input
+example [n]            load an example, the first unless told which
+reload                 read the loaded file again, after editing it
+day <n>                switch to another day of the same year
+quit                   leave";
+
+pub enum Reply
+{
+    Print(String),
+    Quit
+}
+
+/**
+ * A day's input held in memory, so it can be solved and looked at again and again without being
+ * read each time. Files are only read again when asked, or when switching between them
+ */
+pub struct Repl
+{
+    year: String,
+    day: u8,
+    input_dir: PathBuf,
+    files: Vec<PathBuf>,
+    loaded: Option<PathBuf>,
+    lines: Vec<String>
+}
+
+impl Repl
+{
+    pub fn new(year: &str, day: u8) -> Self
+    {
+        let input_dir = input_path(year, day).parent().unwrap().to_path_buf();
+        Self::with_input_dir(year, day, &input_dir)
+    }
+
+    // Starts on the real input if there is one, otherwise the first example
+    pub fn with_input_dir(year: &str, day: u8, input_dir: &Path) -> Self
+    {
+        let mut repl = Self { year: year.to_string(), day, input_dir: input_dir.to_path_buf(), files: Vec::new(), loaded: None, lines: Vec::new() };
+        repl.switch_day(day);
+        repl
+    }
+
+    fn switch_day(&mut self, day: u8)
+    {
+        self.day = day;
+        self.files = input_files(&self.input_dir, day);
+        self.loaded = None;
+        self.lines.clear();
+        if let Some(first) = self.files.first().cloned()
+        {
+            // A file that can't be read is reported when it's used
+            let _ = self.load(&first);
+        }
+    }
+
+    fn load(&mut self, path: &Path) -> anyhow::Result<()>
+    {
+        let text = fs::read_to_string(path).map_err(|e| anyhow::anyhow!("Unable to read {}: {}", path.display(), e))?;
+        self.lines = text.lines().map(|line| line.to_string()).collect();
+        self.loaded = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    fn loaded_name(&self) -> String
+    {
+        self.loaded.as_ref()
+            .and_then(|path| path.file_name())
+            .map_or("no input".to_string(), |name| name.to_string_lossy().to_string())
+    }
+
+    pub fn prompt(&self) -> String
+    {
+        format!("{} day{:02} ({})> ", self.year, self.day, self.loaded_name())
+    }
+
+    fn is_real(&self, path: &Path) -> bool
+    {
+        path.file_name().is_some_and(|name| name.to_string_lossy() == format!("day{:02}.txt", self.day))
+    }
+
+    fn examples(&self) -> Vec<PathBuf>
+    {
+        self.files.iter().filter(|path| !self.is_real(path)).cloned().collect()
+    }
+
+    fn describe_loaded(&self) -> String
+    {
+        format!("Loaded {}, {} lines", self.loaded_name(), self.lines.len())
+    }
+
+    fn input(&self) -> Box<dyn Iterator<Item = &str> + '_>
+    {
+        Box::new(self.lines.iter().map(|s| s.as_str()))
+    }
+
+    fn solve(&self, part: Option<u8>) -> anyhow::Result<String>
+    {
+        let start = Instant::now();
+        let solution = solutions::solve(&self.year, self.day, self.input())?;
+        let time = start.elapsed();
+
+        let answer = match part {
+            Some(1) => format!("Part 1: {}", solution.part1),
+            Some(2) => format!("Part 2: {}", solution.part2),
+            Some(part) => anyhow::bail!("There is no part {}", part),
+            None => solution.to_string()
+        };
+        Ok(format!("{}  ({:.3}ms)", answer, time.as_secs_f64() * 1000.0))
+    }
+
+    fn files(&self) -> String
+    {
+        if self.files.is_empty()
+        {
+            return format!("No input or examples for day {:02} in {}", self.day, self.input_dir.display());
+        }
+        self.files.iter()
+            .map(|path| {
+                let marker = if Some(path) == self.loaded.as_ref() { '*' } else { ' ' };
+                format!("{} {}", marker, path.file_name().unwrap().to_string_lossy())
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn number_arg<T: std::str::FromStr>(arg: Option<&str>, default: T) -> anyhow::Result<T>
+    {
+        match arg {
+            None => Ok(default),
+            Some(arg) => arg.parse().map_err(|_| anyhow::anyhow!("'{}' is not a number", arg))
+        }
+    }
+
+    // Runs one line typed at the prompt. Errors are for showing, and leave the loaded input as it was
+    pub fn command(&mut self, line: &str) -> anyhow::Result<Reply>
+    {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(Reply::Print(String::new()));
+        };
+        let arg = words.next();
+
+        let reply = match command {
+            "part1" | "1" => self.solve(Some(1))?,
+            "part2" | "2" => self.solve(Some(2))?,
+            "solve" => self.solve(None)?,
+            "show" => solutions::inspect(&self.year, self.day, self.input())?,
+            "explain" => {
+                let options = ExplainOptions { part: arg.map_or(Ok(1), parse_part)?, ..ExplainOptions::default() };
+                solutions::explain(&self.year, self.day, self.input(), &options)?
+            },
+            "head" => {
+                let count = Self::number_arg(arg, 10)?;
+                self.lines.iter().take(count).cloned().collect::<Vec<String>>().join("\n")
+            },
+            "files" => self.files(),
+            "real" => {
+                let real = self.files.iter().find(|path| self.is_real(path)).cloned()
+                    .ok_or_else(|| anyhow::anyhow!("There is no real input in {}", self.input_dir.display()))?;
+                self.load(&real)?;
+                self.describe_loaded()
+            },
+            "example" => {
+                let examples = self.examples();
+                let index: usize = Self::number_arg(arg, 1)?;
+                let example = examples.get(index.wrapping_sub(1))
+                    .ok_or_else(|| anyhow::anyhow!("There are {} examples, see 'files'", examples.len()))?
+                    .clone();
+                self.load(&example)?;
+                self.describe_loaded()
+            },
+            "reload" => {
+                // Picks up files added since, as well as changes to the loaded one
+                self.files = input_files(&self.input_dir, self.day);
+                match self.loaded.clone() {
+                    Some(loaded) => self.load(&loaded)?,
+                    None => self.switch_day(self.day)
+                }
+                self.describe_loaded()
+            },
+            "day" => {
+                let days = solutions::days(&self.year).unwrap_or(25);
+                match Self::number_arg(arg, 0)? {
+                    day if (1..=days).contains(&day) => self.switch_day(day),
+                    _ => anyhow::bail!("Invalid day, must be 1-{}", days)
+                }
+                self.describe_loaded()
+            },
+            "help" | "?" => HELP.to_string(),
+            "quit" | "exit" | "q" => return Ok(Reply::Quit),
+            _ => anyhow::bail!("Unknown command '{}', try 'help'", command)
+        };
+
+        Ok(Reply::Print(reply))
+    }
+}
+
+// Reads commands from the terminal until told to quit, or the input ends
+pub fn repl(year: &str, day: u8) -> anyhow::Result<()>
+{
+    let mut repl = Repl::new(year, day);
+    println!("{}. Type 'help' for commands", repl.describe_loaded());
+
+    let stdin = io::stdin();
+    loop
+    {
+        print!("{}", repl.prompt());
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0
+        {
+            println!();
+            return Ok(());
+        }
+
+        match repl.command(&line) {
+            Ok(Reply::Print(text)) if text.is_empty() => (),
+            Ok(Reply::Print(text)) => println!("{}", text),
+            Ok(Reply::Quit) => return Ok(()),
+            Err(e) => println!("Error: {}", e)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "y2024"))]
+mod test
+{
+    use super::*;
+
+    fn reply(repl: &mut Repl, line: &str) -> String
+    {
+        match repl.command(line) {
+            Ok(Reply::Print(text)) => text,
+            Ok(Reply::Quit) => "quit".to_string(),
+            Err(e) => format!("Error: {}", e)
+        }
+    }
+
+    #[test]
+    fn test_repl()
+    {
+        let dir = std::env::temp_dir().join(format!("aoc_repl_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day01.txt"), "1 2\n").unwrap();
+        fs::write(dir.join("day01.example.txt"), "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n").unwrap();
+
+        let mut repl = Repl::with_input_dir("2024", 1, &dir);
+        assert_eq!(repl.prompt(), "2024 day01 (day01.txt)> ");
+        assert!(reply(&mut repl, "part1").starts_with("Part 1: 1  ("));
+
+        assert_eq!(reply(&mut repl, "example"), "Loaded day01.example.txt, 6 lines");
+        assert!(reply(&mut repl, "2").starts_with("Part 2: 31  ("));
+        assert_eq!(reply(&mut repl, "head 2"), "3   4\n4   3");
+        assert_eq!(reply(&mut repl, "files"), "  day01.txt\n* day01.example.txt");
+
+        fs::write(dir.join("day01.example.txt"), "1 1\n").unwrap();
+        assert_eq!(reply(&mut repl, "reload"), "Loaded day01.example.txt, 1 lines");
+        assert!(reply(&mut repl, "solve").starts_with("Part 1: 0 | Part 2: 1  ("));
+        assert_eq!(reply(&mut repl, "real"), "Loaded day01.txt, 1 lines");
+
+        assert_eq!(reply(&mut repl, "example 2"), "Error: There are 1 examples, see 'files'");
+        assert_eq!(reply(&mut repl, "explain 3"), "Error: '3' is not a part, which must be 1 or 2");
+        assert_eq!(reply(&mut repl, "day 26"), "Error: Invalid day, must be 1-25");
+        assert_eq!(reply(&mut repl, "frobnicate"), "Error: Unknown command 'frobnicate', try 'help'");
+
+        assert_eq!(reply(&mut repl, "day 6"), "Loaded no input, 0 lines");
+        assert_eq!(reply(&mut repl, "quit"), "quit");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,20 +1,104 @@
-use std::path::PathBuf;
-use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::fs::{self, File};
 use std::io::{BufRead, BufReader};
 
-use crate::solver::SolutionResult;
+use crate::solver::{ExplainOptions, SolutionResult};
 use crate::solutions;
+use crate::visualise::{self, VisualiseOptions};
 
-pub fn get_solution(year : &str, day_number : u8) -> SolutionResult
+pub fn input_path(year : &str, day_number : u8) -> PathBuf
+{
+    PathBuf::from(format!("./input/{year}/day{:02}.txt", day_number))
+}
+
+/**
+ * The day's input and any example files next to it, named like 'day03.txt', 'day03.example.txt'
+ * or 'day03_part2.txt'. The real input always comes first
+ */
+pub fn input_files(input_dir: &Path, day: u8) -> Vec<PathBuf>
+{
+    let input_name = format!("day{:02}.txt", day);
+    let prefix = format!("day{:02}", day);
+
+    let mut files: Vec<PathBuf> = fs::read_dir(input_dir).into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| is_input_file(name, &prefix)))
+        .collect();
+
+    files.sort_by_key(|path| (!path.ends_with(&input_name), path.clone()));
+    files
+}
+
+pub fn is_input_file(name: &str, prefix: &str) -> bool
 {
-    let input_filepath = PathBuf::from(format!("./input/{year}/day{:02}.txt", day_number));
+    name.strip_prefix(prefix)
+        .and_then(|rest| rest.strip_suffix(".txt"))
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '_', '-']))
+}
+
+pub fn read_input(year : &str, day_number : u8) -> Vec<String>
+{
+    let input_filepath = input_path(year, day_number);
 
     let mut lines: Vec<String> = Vec::new();
 
     if let Ok(file) = File::open(&input_filepath)
     {
-        lines = BufReader::new(file).lines().filter_map(Result::ok).collect();
+        lines = BufReader::new(file).lines().map_while(Result::ok).collect();
     }
+    lines
+}
+
+pub fn get_solution(year : &str, day_number : u8) -> SolutionResult
+{
+    let lines = read_input(year, day_number);
     let lines_iter = lines.iter().map(|s| s.as_str());
     solutions::solve(year, day_number, Box::new(lines_iter))
-}
\ No newline at end of file
+}
+
+// Solves input given as text rather than read from the input folder, as sent to the server
+pub fn solve_text(year : &str, day_number : u8, text : &str) -> SolutionResult
+{
+    solutions::solve(year, day_number, Box::new(text.lines()))
+}
+
+pub fn visualise_day(year : &str, day_number : u8, options: &VisualiseOptions) -> anyhow::Result<()>
+{
+    let lines = read_input(year, day_number);
+    let lines_iter = lines.iter().map(|s| s.as_str());
+    let mut sink = visualise::create_sink(options)?;
+    solutions::visualise(year, day_number, Box::new(lines_iter), options.part, sink.as_mut())?;
+    sink.finish()
+}
+
+pub fn explain_day(year : &str, day_number : u8, options: &ExplainOptions) -> anyhow::Result<String>
+{
+    let lines = read_input(year, day_number);
+    let lines_iter = lines.iter().map(|s| s.as_str());
+    solutions::explain(year, day_number, Box::new(lines_iter), options)
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn test_input_files()
+    {
+        let dir = std::env::temp_dir().join(format!("aoc_runner_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["day03.example.txt", "day03.txt", "day03_part2.txt", "day030.txt", "day04.txt", "day03.rs"]
+        {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let names: Vec<String> = input_files(&dir, 3).iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap().to_string())
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(names, vec!["day03.txt", "day03.example.txt", "day03_part2.txt"]);
+    }
+}
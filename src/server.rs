@@ -0,0 +1,417 @@
+/*!
+ * A local HTTP server, so that other tools can use the solvers without shelling out to the binary.
+ * Only what those tools need of HTTP is understood: one request per connection, with the body
+ * sized by Content-Length
+ *
+ *   POST /solve/{year}/{day}   the input as the body, giving the answers and how long they took
+ *   GET  /days                 the days of each year, and which have been started
+ *   GET  /verify               every day solved and checked against input/{year}/answers.txt
+ */
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::json::{json_array, json_error, json_object, json_string};
+use crate::solver::NotStarted;
+use crate::status::{verify_year, DayStatus};
+use crate::{runner, solutions};
+
+// Puzzle inputs are tens of kilobytes, so anything much bigger is a mistake
+const MAX_BODY: usize = 16 * 1024 * 1024;
+// How long a client has to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct ServeOptions
+{
+    pub address: String,
+    // Requests handled at once, with the rest waiting to be accepted. Also how many solvers may be
+    // running at once, counting those whose requests were given up on
+    pub workers: usize,
+    // How long a request may take before the client is given up on
+    pub timeout: Duration
+}
+
+impl Default for ServeOptions
+{
+    fn default() -> Self
+    {
+        Self { address: "127.0.0.1:8024".to_string(), workers: 4, timeout: Duration::from_secs(30) }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Response
+{
+    status: u16,
+    body: String
+}
+
+impl Response
+{
+    fn ok(body: String) -> Self
+    {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self
+    {
+        Self { status, body: json_error(message) }
+    }
+
+    fn reason(&self) -> &'static str
+    {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            501 => "Not Implemented",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "Internal Server Error"
+        }
+    }
+}
+
+struct Request
+{
+    method: String,
+    path: String,
+    body: String
+}
+
+fn solve(year: &str, day: &str, input: &str) -> Response
+{
+    let Some(days) = solutions::days(year) else {
+        return Response::error(404, &format!("{} Not Implemented", year));
+    };
+    if !solutions::compiled_in(year)
+    {
+        return Response::error(404, &solutions::not_compiled_in(year).to_string());
+    }
+    let day = match day.parse::<u8>() {
+        Ok(day) if (1..=days).contains(&day) => day,
+        _ => return Response::error(404, &format!("Invalid day, must be 1-{}", days))
+    };
+
+    let start = Instant::now();
+    let result = runner::solve_text(year, day, input);
+    let seconds = start.elapsed().as_secs_f64();
+
+    match result {
+        Ok(solution) => Response::ok(json_object([
+            ("year", json_string(year)),
+            ("day", day.to_string()),
+            ("part1", solution.part1.to_string()),
+            ("part2", solution.part2.to_string()),
+            ("seconds", seconds.to_string())
+        ])),
+        Err(e) if e.is::<NotStarted>() => Response::error(501, &e.to_string()),
+        Err(e) => Response::error(422, &e.to_string())
+    }
+}
+
+fn days() -> Response
+{
+    Response::ok(json_object(solutions::YEARS.iter()
+        .filter(|year| solutions::compiled_in(year))
+        .map(|year| {
+            let days = solutions::days(year).unwrap();
+            let started = (1..=days).filter(|day| solutions::started(year, *day)).map(|day| day.to_string());
+            (year, json_object([("days", days.to_string()), ("started", json_array(started))]))
+        })))
+}
+
+fn day_json(status: &DayStatus) -> String
+{
+    json_object([
+        ("day", status.day.to_string()),
+        ("input", status.input.to_string()),
+        ("part1", json_string(status.parts[0].name())),
+        ("part2", json_string(status.parts[1].name())),
+        ("seconds", status.time.map_or("null".to_string(), |time| time.as_secs_f64().to_string()))
+    ])
+}
+
+fn verify() -> Response
+{
+    let mut years = Vec::new();
+    for year in solutions::YEARS.iter().filter(|year| solutions::compiled_in(year))
+    {
+        match verify_year(year, solutions::days(year).unwrap()) {
+            Ok(statuses) => years.push((year, json_array(statuses.iter().map(day_json)))),
+            Err(e) => return Response::error(500, &e.to_string())
+        }
+    }
+    Response::ok(json_object(years))
+}
+
+fn route(request: &Request) -> Response
+{
+    // Query strings aren't used by anything, so are ignored
+    let path = request.path.split('?').next().unwrap();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("POST", ["solve", year, day]) => solve(year, day, &request.body),
+        ("GET", ["days"]) => days(),
+        ("GET", ["verify"]) => verify(),
+        (_, ["solve", _, _] | ["days"] | ["verify"]) => Response::error(405, &format!("{} isn't allowed on {}", request.method, path)),
+        _ => Response::error(404, &format!("Nothing at {}", path))
+    }
+}
+
+/**
+ * Counts the requests being routed, including those given up on whose solvers are still running.
+ * Threads can't be stopped, so without a limit slow solvers would pile up with every retry
+ */
+#[derive(Clone)]
+struct Slots
+{
+    taken: Arc<AtomicUsize>,
+    limit: usize
+}
+
+// Held for as long as a request's solver runs, giving the slot back when dropped
+struct Slot(Arc<AtomicUsize>);
+
+impl Slots
+{
+    fn new(limit: usize) -> Self
+    {
+        Self { taken: Arc::new(AtomicUsize::new(0)), limit }
+    }
+
+    fn take(&self) -> Option<Slot>
+    {
+        self.taken.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |taken| (taken < self.limit).then_some(taken + 1)).ok()?;
+        Some(Slot(self.taken.clone()))
+    }
+}
+
+impl Drop for Slot
+{
+    fn drop(&mut self)
+    {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/**
+ * Does the work on a thread of its own, so a slow solver can be given up on after the timeout,
+ * and a solver that panics only loses its own request. The thread keeps its slot until the work
+ * finishes, and with every slot taken the request is turned away straight away
+ */
+fn with_timeout(work: impl FnOnce() -> Response + Send + 'static, slots: &Slots, timeout: Duration) -> Response
+{
+    let Some(slot) = slots.take() else {
+        return Response::error(503, &format!("All {} solvers are busy, try again later", slots.limit));
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _slot = slot;
+        let _ = sender.send(work());
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(response) => response,
+        Err(RecvTimeoutError::Timeout) => Response::error(504, &format!("Gave up after {}s", timeout.as_secs_f64())),
+        Err(RecvTimeoutError::Disconnected) => Response::error(500, "The solver panicked")
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request, Response>
+{
+    let bad_request = |message: &str| Response::error(400, message);
+    let mut reader = BufReader::new(stream);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| bad_request(&e.to_string()))?;
+    let mut request_line = line.split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err(bad_request("Malformed request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut length = 0;
+    let mut expects_continue = false;
+    loop
+    {
+        line.clear();
+        reader.read_line(&mut line).map_err(|e| bad_request(&e.to_string()))?;
+        let Some((name, value)) = line.trim().split_once(':') else {
+            break;
+        };
+        if name.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse().map_err(|_| bad_request("Malformed Content-Length"))?;
+        }
+        else if name.eq_ignore_ascii_case("expect")
+        {
+            expects_continue = value.trim().eq_ignore_ascii_case("100-continue");
+        }
+    }
+
+    if length > MAX_BODY
+    {
+        return Err(Response::error(413, &format!("Bodies are limited to {} bytes", MAX_BODY)));
+    }
+    // curl waits to be told to go ahead before sending larger inputs
+    if expects_continue
+    {
+        let mut writer = *reader.get_ref();
+        let _ = writer.write_all(b"HTTP/1.1 100 Continue\r\n\r\n");
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|e| bad_request(&e.to_string()))?;
+    let body = String::from_utf8(body).map_err(|_| bad_request("The body must be UTF-8"))?;
+
+    Ok(Request { method, path, body })
+}
+
+fn handle_connection(stream: TcpStream, slots: &Slots, timeout: Duration) -> anyhow::Result<()>
+{
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let start = Instant::now();
+
+    let (description, response) = match read_request(&stream) {
+        Ok(request) => (format!("{} {}", request.method, request.path), with_timeout(move || route(&request), slots, timeout)),
+        Err(response) => ("-".to_string(), response)
+    };
+    println!("{} {} {}ms", description, response.status, start.elapsed().as_millis());
+
+    let mut stream = stream;
+    write!(stream, "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status, response.reason(), response.body.len(), response.body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+// Each worker takes connections from the listener in turn, which is what limits the concurrency
+fn run(listener: TcpListener, options: &ServeOptions) -> anyhow::Result<()>
+{
+    let slots = Slots::new(options.workers.max(1));
+    let mut workers = Vec::new();
+    for _ in 0..options.workers.max(1)
+    {
+        let listener = listener.try_clone()?;
+        let slots = slots.clone();
+        let timeout = options.timeout;
+        workers.push(thread::spawn(move || {
+            for stream in listener.incoming()
+            {
+                if let Err(e) = stream.map_err(anyhow::Error::from).and_then(|stream| handle_connection(stream, &slots, timeout))
+                {
+                    eprintln!("{}", e);
+                }
+            }
+        }));
+    }
+
+    for worker in workers
+    {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+// Serves requests until the process is stopped
+pub fn serve(options: &ServeOptions) -> anyhow::Result<()>
+{
+    let listener = TcpListener::bind(&options.address)?;
+    println!("Listening on http://{} with {} workers, giving up on requests after {}s",
+        listener.local_addr()?, options.workers, options.timeout.as_secs_f64());
+    run(listener, options)
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    fn request(method: &str, path: &str, body: &str) -> Request
+    {
+        Request { method: method.to_string(), path: path.to_string(), body: body.to_string() }
+    }
+
+    #[test]
+    fn test_route()
+    {
+        assert_eq!(route(&request("GET", "/nowhere", "")).status, 404);
+        assert_eq!(route(&request("GET", "/solve/2024/1", "")).status, 405);
+        assert_eq!(route(&request("POST", "/solve/1999/1", "")), Response::error(404, "1999 Not Implemented"));
+    }
+
+    #[cfg(all(feature = "y2024", feature = "y2025"))]
+    #[test]
+    fn test_solve()
+    {
+        let response = route(&request("POST", "/solve/2024/1", "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n"));
+        assert_eq!(response.status, 200);
+        assert!(response.body.starts_with("{\"year\": \"2024\", \"day\": 1, \"part1\": 11, \"part2\": 31, \"seconds\": "));
+
+        assert_eq!(route(&request("POST", "/solve/2025/1", "")), Response::error(501, "not started"));
+        assert_eq!(route(&request("POST", "/solve/2025/13", "")), Response::error(404, "Invalid day, must be 1-12"));
+        assert_eq!(route(&request("POST", "/solve/2024/1", "1 2 3")).status, 422);
+
+        assert_eq!(route(&request("GET", "/days?pretty", "")).body,
+            "{\"2024\": {\"days\": 25, \"started\": [1, 2, 3, 4, 5, 6]}, \"2025\": {\"days\": 12, \"started\": []}}");
+    }
+
+    #[test]
+    fn test_timeout()
+    {
+        let slots = Slots::new(1);
+        let (release, released) = mpsc::channel::<()>();
+        let response = with_timeout(move || {
+            let _ = released.recv();
+            Response::ok(String::new())
+        }, &slots, Duration::from_millis(10));
+        assert_eq!(response.status, 504);
+
+        // The work given up on still has the only slot until it finishes
+        let quick = || Response::ok(String::new());
+        assert_eq!(with_timeout(quick, &slots, Duration::from_secs(1)).status, 503);
+        release.send(()).unwrap();
+        let start = Instant::now();
+        while slots.taken.load(Ordering::SeqCst) > 0 && start.elapsed() < Duration::from_secs(5)
+        {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(with_timeout(quick, &slots, Duration::from_secs(1)).status, 200);
+    }
+
+    #[cfg(all(feature = "y2024", feature = "y2025"))]
+    #[test]
+    fn test_serve()
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || run(listener, &ServeOptions::default()));
+
+        let send = |request: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        let response = send(&format!("POST /solve/2024/1 HTTP/1.1\r\nContent-Length: {}\r\nExpect: 100-continue\r\n\r\n{}", input.len(), input));
+        assert!(response.starts_with("HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.contains("\"part1\": 11, \"part2\": 31"), "{}", response);
+
+        assert!(send("GET /days HTTP/1.1\r\n\r\n").contains("\"2025\": {\"days\": 12, \"started\": []}"));
+        assert!(send("BROKEN\r\n\r\n").starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        assert!(send("POST /solve/2024/1 HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n").starts_with("HTTP/1.1 413"));
+    }
+}
@@ -1,24 +1,126 @@
-mod yr2024;
-mod yr2025;
+#[cfg(feature = "y2024")]
+pub mod yr2024;
+#[cfg(feature = "y2025")]
+pub mod yr2025;
 
-use crate::solver::{Solver, SolutionResult};
+use crate::generate::GenerateOptions;
+use crate::solver::{ExplainOptions, NotStarted, SolutionResult, Solver};
+use crate::visualise::FrameSink;
 
-pub fn solve<'a>(year: &str, day_number : u8, lines: Box<dyn Iterator<Item = &'a str> + 'a>) -> SolutionResult
+pub const YEARS: [&str; 2] = ["2024", "2025"];
+
+type Lines<'a> = Box<dyn Iterator<Item = &'a str> + 'a>;
+
+/**
+ * A day's solver as a row of the registry, each year listing its days once in its own module.
+ * Holding the solver's functions rather than its type lets every day of a year share one table
+ */
+pub struct Day
 {
-    match year {
-        "2024" => match day_number {
-                1 => yr2024::day01::SolverDay01::solve(lines),
-                2 => yr2024::day02::SolverDay02::solve(lines),
-                3 => yr2024::day03::SolverDay03::solve(lines),
-                4 => yr2024::day04::SolverDay04::solve(lines),
-                5 => yr2024::day05::SolverDay05::solve(lines),
-                6 => yr2024::day06::SolverDay06::solve(lines),
-                _ => Err(anyhow::anyhow!("{} day {} Not Implemented", year, day_number))
-        },
-        "2025" => match day_number {
-                _ => Err(anyhow::anyhow!("{} day {} Not Implemented", year, day_number))
+    pub day: u8,
+    // Days with only a stub so far are registered as not started, and answer as such
+    pub started: bool,
+    solve: for<'a> fn(Lines<'a>) -> SolutionResult,
+    visualise: for<'a> fn(Lines<'a>, u8, &mut dyn FrameSink) -> anyhow::Result<()>,
+    explain: for<'a> fn(Lines<'a>, &ExplainOptions) -> anyhow::Result<String>,
+    inspect: for<'a> fn(Lines<'a>) -> anyhow::Result<String>,
+    generate: fn(&GenerateOptions) -> anyhow::Result<String>
+}
 
+impl Day
+{
+    pub const fn of<S: Solver>(day: u8) -> Self
+    {
+        Day {
+            day,
+            started: true,
+            solve: S::solve,
+            visualise: S::visualise,
+            explain: S::explain,
+            inspect: S::inspect,
+            generate: S::generate
         }
-        _ => Err(anyhow::anyhow!(format!("{} Not Implemented", year)))
     }
-}
\ No newline at end of file
+
+    pub const fn not_started(self) -> Self
+    {
+        Day { started: false, ..self }
+    }
+}
+
+// Whether the year's solutions were built, each year having a Cargo feature named like 'y2024'
+pub fn compiled_in(year: &str) -> bool
+{
+    (year == "2024" && cfg!(feature = "y2024")) || (year == "2025" && cfg!(feature = "y2025"))
+}
+
+pub fn not_compiled_in(year: &str) -> anyhow::Error
+{
+    anyhow::anyhow!("{} not compiled in, build with '--features y{}' to include it", year, year)
+}
+
+// Number of puzzles in each year there are solutions for. 2025 was the first year with only 12
+pub fn days(year: &str) -> Option<u8>
+{
+    match year {
+        "2024" => Some(25),
+        "2025" => Some(12),
+        _ => None
+    }
+}
+
+// Read from the registry, without running anything. Days of unknown years, years not compiled in, or past a year's end were never started
+pub fn started(year: &str, day_number: u8) -> bool
+{
+    find(year, day_number).is_ok()
+}
+
+// The registered days of each year compiled in
+fn registered(year: &str) -> anyhow::Result<&'static [Day]>
+{
+    match year {
+        #[cfg(feature = "y2024")]
+        "2024" => Ok(yr2024::DAYS),
+        #[cfg(feature = "y2025")]
+        "2025" => Ok(yr2025::DAYS),
+        year if YEARS.contains(&year) => Err(not_compiled_in(year)),
+        _ => Err(anyhow::anyhow!("{} Not Implemented", year))
+    }
+}
+
+// Days of the year that aren't registered yet haven't been started, and days past its end don't exist
+fn find(year: &str, day_number: u8) -> anyhow::Result<&'static Day>
+{
+    let registered = registered(year)?;
+    match registered.iter().find(|day| day.day == day_number) {
+        Some(day) if day.started => Ok(day),
+        Some(_) => Err(NotStarted.into()),
+        None if (1..=days(year).unwrap_or(0)).contains(&day_number) => Err(NotStarted.into()),
+        None => Err(anyhow::anyhow!("{} day {} Not Implemented", year, day_number))
+    }
+}
+
+pub fn solve<'a>(year: &str, day_number : u8, lines: Lines<'a>) -> SolutionResult
+{
+    (find(year, day_number)?.solve)(lines)
+}
+
+pub fn visualise<'a>(year: &str, day_number : u8, lines: Lines<'a>, part: u8, sink: &mut dyn FrameSink) -> anyhow::Result<()>
+{
+    (find(year, day_number)?.visualise)(lines, part, sink)
+}
+
+pub fn explain<'a>(year: &str, day_number : u8, lines: Lines<'a>, options: &ExplainOptions) -> anyhow::Result<String>
+{
+    (find(year, day_number)?.explain)(lines, options)
+}
+
+pub fn inspect<'a>(year: &str, day_number : u8, lines: Lines<'a>) -> anyhow::Result<String>
+{
+    (find(year, day_number)?.inspect)(lines)
+}
+
+pub fn generate(year: &str, day_number : u8, options: &GenerateOptions) -> anyhow::Result<String>
+{
+    (find(year, day_number)?.generate)(options)
+}
@@ -1,53 +1,61 @@
-use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::HashMap;
-
+use crate::columns::Columns;
+use crate::generate::GenerateOptions;
 use crate::solver::{Solver, Solution, SolutionResult};
 
 pub struct SolverDay01 {}
 
 impl Solver for SolverDay01
 {
-    fn solve_impl<'a>(lines: Vec<&'a str>) -> SolutionResult
-    {    
-        let mut col1: Vec<isize> = Vec::new();
-        let mut col2: Vec<isize> = Vec::new();
+    // Columns trims and skips blank lines itself, so the lines are kept as they are to number malformed rows as in the file
+    fn clean<'a>(lines: Box<dyn Iterator<Item = &'a str> + 'a>) -> Vec<&'a str>
+    {
+        lines.collect()
+    }
 
-        for line in lines
+    /**
+     * Rows that aren't two numbers are an error naming their lines, rather than being skipped as
+     * they once were, since leaving a row out quietly changes both answers
+     */
+    fn solve_impl(lines: Vec<&str>) -> SolutionResult
+    {
+        let lists = Columns::from_lines(2, lines);
+        if !lists.malformed().is_empty()
         {
-            let cols = line.split_whitespace().collect::<Vec<&str>>();
-            if cols.len() != 2
-            {
-                continue;
-            }
-            col1.push(cols[0].parse::<isize>()?);
-            col2.push(cols[1].parse::<isize>()?);
+            anyhow::bail!("Lines {:?} are not two numbers", lists.malformed());
         }
-        col1.sort();
-        col2.sort();
-
-        let mut col2_counter = HashMap::new();
-        
-        for value in col2.iter() {
-            match col2_counter.entry(value) {
-                Occupied(mut e) => *e.get_mut() += 1,
-                Vacant(e) => {
-                    e.insert(1);
-                }
-            }
+
+        Ok(Solution {
+            part1: lists.distance(0, 1).ok_or_else(|| anyhow::anyhow!("The total distance is too big to count"))?,
+            part2: lists.similarity(0, 1).ok_or_else(|| anyhow::anyhow!("The similarity score is too big to count"))?
+        })
+    }
+
+    /**
+     * Two columns of numbers with 'digits' digits. 'repeats' is the chance of a number on the
+     * right being copied from the left, so that part 2 has something to count
+     */
+    fn generate(options: &GenerateOptions) -> anyhow::Result<String>
+    {
+        let rows: usize = options.knob("rows", 1000)?;
+        let digits: u32 = options.knob("digits", 5)?;
+        let repeats = options.ratio("repeats", 0.3)?;
+        if !(1..=18).contains(&digits)
+        {
+            anyhow::bail!("digits must be 1-18");
         }
 
-        Ok(col1
-            .iter()
-            .zip(col2.iter())
-            .fold(Solution::default(), |mut res, (col1_v, col2_v)| {
-                res.part1 += (*col1_v - *col2_v).abs();
-                res.part2 += *col1_v * col2_counter.get(col1_v).unwrap_or(&0);
-                res
-            })
-        )
+        let mut random = options.random();
+        let (low, high) = (10isize.pow(digits - 1), 10isize.pow(digits) - 1);
+        let left: Vec<isize> = (0..rows).map(|_| random.between(low, high)).collect();
+        let right: Vec<isize> = (0..rows)
+            .map(|_| if random.chance(repeats) { *random.choose(&left) } else { random.between(low, high) })
+            .collect();
+
+        Ok(left.iter().zip(&right).map(|(left, right)| format!("{}   {}\n", left, right)).collect())
     }
 }
 
+
 #[cfg(test)]
 mod test
 {
@@ -70,5 +78,38 @@ mod test
         assert_eq!(solution.part1, 11);
         assert_eq!(solution.part2, 31);
     }
-}
 
+    #[test]
+    fn test_generate()
+    {
+        let options = GenerateOptions::new(1).with_knobs("rows=50,digits=3,repeats=1").unwrap();
+        let input = SolverDay01::generate(&options).unwrap();
+        assert_eq!(input, SolverDay01::generate(&options).unwrap());
+        assert_eq!(input.lines().count(), 50);
+        assert!(input.lines().all(|line| line.len() == 9));
+        assert!(SolverDay01::solve(Box::new(input.lines())).unwrap().part2 > 0);
+    }
+
+    #[test]
+    fn test_malformed_rows()
+    {
+        let sample: &str = "
+            3   4
+
+            4
+            2   5
+            x   1
+        ";
+
+        // Malformed rows are an error rather than skipped. Blank lines count too, so these are the lines to look at in the file
+        let error = SolverDay01::solve(Box::new(sample.split('\n'))).unwrap_err();
+        assert_eq!(error.to_string(), "Lines [4, 6] are not two numbers");
+    }
+
+    #[test]
+    fn test_overflow()
+    {
+        let error = SolverDay01::solve(Box::new(["9223372036854775807 -1"].into_iter())).unwrap_err();
+        assert_eq!(error.to_string(), "The total distance is too big to count");
+    }
+}
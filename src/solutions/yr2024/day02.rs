@@ -1,8 +1,10 @@
-use crate::solver::{Solver, Solution, SolutionResult};
+use crate::generate::{GenerateOptions, Random};
+use crate::solver::{ExplainOptions, Solver, Solution, SolutionResult};
+use crate::visualise::{Colour, RESET};
 
 /**
  * Sequences that are safe if one element is removed are "Dampened", which stores the index
- * of the removed element for debugging purposes
+ * of the removed element
  */
 #[derive(PartialEq, Eq, Debug)]
 enum ReportSafety
@@ -12,124 +14,216 @@ enum ReportSafety
     Safe
 }
 
-fn downgrade(input: &ReportSafety, index: &usize) -> ReportSafety
+/**
+ * Checks reports against the rules for how far apart neighbouring levels may be, allowing up to
+ * 'removals' levels to be taken out. Every step in a safe report goes the same way, and is at
+ * least 'min_step' and at most 'max_step' in size
+ */
+#[derive(Copy, Clone, Debug)]
+struct ReportChecker
 {
-    match input {
-        ReportSafety::Safe => ReportSafety::Dampened(index.clone()),
-        ReportSafety::Dampened(_) => ReportSafety::Unsafe,
-        ReportSafety::Unsafe => ReportSafety::Unsafe,
-    }
+    min_step: i32,
+    max_step: i32,
+    removals: usize
 }
 
-const THRESHOLD: i32 = 3;
-
-pub struct SolverDay02 {}
+const DIRECTIONS: [i32; 2] = [1, -1];
 
-impl SolverDay02
+impl ReportChecker
 {
+    // Widened first, as levels far apart can step further than an i32 holds
+    fn safe_step(&self, v1: i32, v2: i32, direction: i32) -> bool
+    {
+        (self.min_step as i64..=self.max_step as i64).contains(&((v2 as i64 - v1 as i64) * direction as i64))
+    }
+
+    fn first_failure_in(&self, report: &[i32], direction: i32) -> Option<usize>
+    {
+        (1..report.len()).find(|&idx| !self.safe_step(report[idx - 1], report[idx], direction))
+    }
+
     /**
-     * A 'Safe' Pair has a difference of at least 1 and at most 3, in the 
-     * same direction as the sequence it is contained in
+     * Index of the second level of the first unsafe pair, if there is one. The report goes the
+     * way that gets furthest before failing, which is the way of its first step
      */
-    fn determine_safe_pair(v1: &i32, v2: &i32, direction: &mut i32) -> bool
+    fn first_failure(&self, report: &[i32]) -> Option<usize>
     {
-        let diff = v2 - v1;
-        
-        let abs_diff = diff.abs();
-        let diff_sign = diff.checked_div(abs_diff).unwrap_or(0);
+        let failures: Option<Vec<usize>> = DIRECTIONS.iter().map(|&direction| self.first_failure_in(report, direction)).collect();
+        failures?.into_iter().max()
+    }
 
-        let mut result = true;
-        if abs_diff > THRESHOLD || abs_diff == 0 || diff_sign + *direction == 0
+    /**
+     * Longest run of levels that can be kept going one way. Of the longest, it is the one that
+     * keeps the latest levels, so the levels removed are the earliest possible
+     */
+    fn longest_kept(&self, report: &[i32], direction: i32) -> Vec<usize>
+    {
+        // Length of the longest run starting at each level
+        let mut longest = vec![1; report.len()];
+        for start in (0..report.len()).rev()
         {
-            result = false
-        }        
-        else
+            longest[start] = (start + 1..report.len())
+                .filter(|&next| self.safe_step(report[start], report[next], direction))
+                .map(|next| longest[next] + 1)
+                .max()
+                .unwrap_or(1);
+        }
+
+        let Some(&length) = longest.iter().max() else {
+            return Vec::new();
+        };
+        let mut kept = vec![(0..report.len()).rev().find(|&idx| longest[idx] == length).unwrap()];
+        while kept.len() < length
         {
-            *direction = diff_sign;
+            let last = kept[kept.len() - 1];
+            let next = (last + 1..report.len()).rev()
+                .find(|&next| longest[next] == length - kept.len() && self.safe_step(report[last], report[next], direction))
+                .unwrap();
+            kept.push(next);
         }
-        
-        result
+        kept
+    }
+
+    /**
+     * The earliest single level whose removal makes the report safe. Whichever way the report
+     * ends up going, the removal has to split the first pair that fails going that way, so only
+     * those levels are tried. That is a few passes over the report, rather than the search below
+     */
+    fn single_removal(&self, report: &[i32]) -> Option<usize>
+    {
+        let mut candidates: Vec<usize> = DIRECTIONS.iter()
+            .filter_map(|&direction| self.first_failure_in(report, direction))
+            .flat_map(|failure| [failure - 1, failure])
+            .collect();
+        candidates.sort();
+        candidates.dedup();
+        candidates.into_iter().find(|&idx| self.first_failure(&[&report[..idx], &report[idx + 1..]].concat()).is_none())
     }
 
     /**
-     * Determine safe is called recursively once on the list with just the
-     * first element removed, as the direction criteria means it is 
-     * undecidable which of the first two elements to remove if they are unsafe
-     * without looking ahead in the list
+     * The smallest set of levels to remove to make the report safe, or None if that takes more
+     * than the removals allowed. Of the smallest sets, the one earliest in the report is chosen
      */
-    fn determine_safe_rec(report: &[i32], recurse: bool) -> ReportSafety
+    fn removals(&self, report: &[i32]) -> Option<Vec<usize>>
     {
-        let mut direction = 0;
- 
-        let mut safety = ReportSafety::Safe;
+        if self.first_failure(report).is_none()
+        {
+            return Some(Vec::new());
+        }
+        // The puzzle's dampener only ever takes one level out, which doesn't need the longest kept run
+        match self.removals {
+            0 => return None,
+            1 => return self.single_removal(report).map(|idx| vec![idx]),
+            _ => ()
+        }
+
+        // Comparing kept levels from the end prefers those keeping later levels
+        let kept = DIRECTIONS.iter()
+            .map(|&direction| self.longest_kept(report, direction))
+            .max_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+            .unwrap();
 
-        let mut va_idx = 0;
-        let mut vb_idx = 1;
+        let removed: Vec<usize> = (0..report.len()).filter(|idx| !kept.contains(idx)).collect();
+        (removed.len() <= self.removals).then_some(removed)
+    }
+}
 
-        if report.len() < 2
+// The rules from the puzzle, with the Problem Dampener allowing one level to be removed
+const DAMPENER: ReportChecker = ReportChecker { min_step: 1, max_step: 3, removals: 1 };
+
+pub struct SolverDay02 {}
+
+impl SolverDay02
+{
+    fn determine_safe(report: &[i32]) -> ReportSafety
+    {
+        match DAMPENER.removals(report).as_deref() {
+            None => ReportSafety::Unsafe,
+            Some([]) => ReportSafety::Safe,
+            Some(removed) => ReportSafety::Dampened(removed[0])
+        }
+    }
+
+    fn first_failure(report: &[i32]) -> Option<usize>
+    {
+        DAMPENER.first_failure(report)
+    }
+
+    // Tries removing each level in turn, as a check on determine_safe and for the generator
+    fn brute_force_safety(report: &[i32]) -> ReportSafety
+    {
+        let is_safe = |levels: &[i32]| Self::first_failure(levels).is_none();
+        if is_safe(report)
         {
             return ReportSafety::Safe;
         }
+        (0..report.len())
+            .find(|&idx| is_safe(&[&report[..idx], &report[idx + 1..]].concat()))
+            .map_or(ReportSafety::Unsafe, ReportSafety::Dampened)
+    }
 
-        while vb_idx < report.len()
-        {
-            if !Self::determine_safe_pair(&report[va_idx], &report[vb_idx], &mut direction)
+    // Levels that only ever step 1-3 the same way, staying above 0
+    fn safe_report(random: &mut Random, length: usize) -> Vec<i32>
+    {
+        let direction = *random.choose(&DIRECTIONS);
+        let mut level = random.between(10, 99) as i32 + if direction < 0 { 3 * length as i32 } else { 0 };
+        (0..length).map(|_| {
+            level += direction * random.between(1, 3) as i32;
+            level
+        }).collect()
+    }
+
+    // Puts in a level that repeats its neighbour, jumps too far, or turns back
+    fn add_fault(random: &mut Random, report: &mut Vec<i32>)
+    {
+        let position = random.below(report.len() + 1);
+        let neighbour = report[position.saturating_sub(1).min(report.len() - 1)];
+        let level = match random.below(3) {
+            0 => neighbour,
+            1 => neighbour + random.choose(&DIRECTIONS) * random.between(4, 9) as i32,
+            _ => neighbour - (report[report.len() - 1] - report[0]).signum() * random.between(1, 3) as i32
+        };
+        report.insert(position, level.max(1));
+    }
+
+    fn parse_report(line: &str) -> anyhow::Result<Vec<i32>>
+    {
+        Ok(line.split_whitespace().map(|x| x.parse::<i32>()).collect::<Result<Vec<i32>, _>>()?)
+    }
+
+    // The levels of a report, with the failing pair and the removed level picked out
+    fn describe_levels(report: &[i32], failure: Option<usize>, removed: Option<usize>, plain: bool) -> String
+    {
+        report.iter().enumerate().map(|(idx, level)| {
+            let colour = if removed == Some(idx)
             {
-                safety = downgrade(&safety, &vb_idx);
-                if safety == ReportSafety::Unsafe
-                {
-                    break;
-                }
-                if recurse && va_idx < 2
-                {
-                    let tail = &report[1..];
-                    let tail_safety = Self::determine_safe_rec(tail, false);
-                    if tail_safety == ReportSafety::Safe
-                    {
-                        return ReportSafety::Dampened(0);
-                    }
-                    else if va_idx == 1
-                    {
-                        direction = 0;
-                        va_idx = 0;
-                        safety = match tail_safety {
-                            ReportSafety::Dampened(idx) => ReportSafety::Dampened(idx + 1),
-                            ReportSafety::Unsafe => ReportSafety::Dampened(1),
-                            _ => safety 
-                        };
-                        if tail.len() <= 2 
-                        {
-                            break
-                        }
-                        continue;
-                    }
-                }
+                Some(Colour::Red)
+            }
+            else if failure.is_some_and(|failure| idx + 1 == failure || idx == failure)
+            {
+                Some(Colour::Yellow)
             }
             else
             {
-                va_idx = vb_idx;
+                None
+            };
+            match colour {
+                Some(colour) if !plain => format!("{}{}{}", colour.escape(), level, RESET),
+                _ => level.to_string()
             }
-            vb_idx += 1;
-        }
-
-        safety
-    }
-
-    fn determine_safe(report: &[i32]) -> ReportSafety
-    {
-        Self::determine_safe_rec(report, true)
+        }).collect::<Vec<String>>().join(" ")
     }
 }
 
 impl Solver for SolverDay02
 {
-    fn solve_impl<'a>(lines: Vec<&'a str>) -> SolutionResult
+    fn solve_impl(lines: Vec<&str>) -> SolutionResult
     {
         let mut result = Solution::default();
 
         for line in lines
         {
-            let row_items: Vec<i32> = line.split_whitespace().map(|x| x.parse::<i32>().unwrap()).collect();
+            let row_items = Self::parse_report(line)?;
 
             let line_safety = Self::determine_safe(&row_items);
 
@@ -139,69 +233,169 @@ impl Solver for SolverDay02
 
         Ok(result)
     }
+
+    /**
+     * Gives the verdict for each report, the first pair of levels that breaks the rules and, in
+     * part 2, the level the dampener removed. Colours show the failing pair in yellow and the
+     * removed level in red, unless --plain is given
+     */
+    fn explain_impl(lines: Vec<&str>, options: &ExplainOptions) -> anyhow::Result<String>
+    {
+        let mut explanation = String::new();
+        let mut safe_count = 0;
+
+        for line in lines
+        {
+            let report = Self::parse_report(line)?;
+            let failure = Self::first_failure(&report);
+            let removed = match Self::determine_safe(&report) {
+                ReportSafety::Dampened(idx) if options.part == 2 => Some(idx),
+                _ => None
+            };
+
+            let verdict = match (failure, removed) {
+                (None, _) => "safe".to_string(),
+                (Some(_), None) => "unsafe".to_string(),
+                (Some(_), Some(idx)) => format!("dampened (removed level {} at index {})", report[idx], idx)
+            };
+            let detail = match failure {
+                Some(idx) => format!(", {} -> {} fails", report[idx - 1], report[idx]),
+                None => String::new()
+            };
+            safe_count += (failure.is_none() || removed.is_some()) as isize;
+
+            // Escapes take up no room on screen, so pad by the width of the plain levels
+            let padding = 24usize.saturating_sub(Self::describe_levels(&report, None, None, true).len());
+            let levels = Self::describe_levels(&report, failure, removed, options.plain);
+            explanation.push_str(&format!("{}{} {}{}\n", levels, " ".repeat(padding), verdict, detail));
+        }
+
+        explanation.push_str(&format!("Part {}: {} safe reports", options.part, safe_count));
+        Ok(explanation)
+    }
+
+    /**
+     * Reports of 'min_levels' to 'max_levels' levels, in a random order. Exactly the 'unsafe'
+     * proportion of them are unsafe even with the dampener, and the 'dampened' proportion are only
+     * safe with it. Each is checked by removing every level in turn
+     */
+    fn generate(options: &GenerateOptions) -> anyhow::Result<String>
+    {
+        let reports: usize = options.knob("reports", 1000)?;
+        let min_levels: usize = options.knob("min_levels", 5)?;
+        let max_levels: usize = options.knob("max_levels", 8)?;
+        let unsafe_count = (options.ratio("unsafe", 0.5)? * reports as f64).round() as usize;
+        let dampened_count = (options.ratio("dampened", 0.2)? * reports as f64).round() as usize;
+        if min_levels < 3 || min_levels > max_levels
+        {
+            anyhow::bail!("Reports need at least 3 levels, and min_levels can't be more than max_levels");
+        }
+        if unsafe_count + dampened_count > reports
+        {
+            anyhow::bail!("unsafe and dampened add up to more than every report");
+        }
+
+        let mut random = options.random();
+        let mut kinds: Vec<ReportSafety> = (0..reports).map(|index| match index {
+            index if index < unsafe_count => ReportSafety::Unsafe,
+            index if index < unsafe_count + dampened_count => ReportSafety::Dampened(0),
+            _ => ReportSafety::Safe
+        }).collect();
+        random.shuffle(&mut kinds);
+
+        let mut input = String::new();
+        for kind in kinds
+        {
+            let length = random.between(min_levels as isize, max_levels as isize) as usize;
+            let faults = match kind {
+                ReportSafety::Safe => 0,
+                ReportSafety::Dampened(_) => 1,
+                ReportSafety::Unsafe => 2
+            };
+            let report = loop
+            {
+                let mut report = Self::safe_report(&mut random, length - faults);
+                for _ in 0..faults
+                {
+                    Self::add_fault(&mut random, &mut report);
+                }
+                let safety = Self::brute_force_safety(&report);
+                if std::mem::discriminant(&safety) == std::mem::discriminant(&kind)
+                {
+                    break report;
+                }
+            };
+            let levels: Vec<String> = report.iter().map(|level| level.to_string()).collect();
+            input.push_str(&levels.join(" "));
+            input.push('\n');
+        }
+        Ok(input)
+    }
 }
 
 #[cfg(test)]
 mod test
 {
+    use itertools::Itertools;
+
     use super::*;
 
      #[test]
     fn test_base_cases()
     {
-        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&vec![]));
-        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&vec![0]));
-        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&vec![0, 1]));
-        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&vec![0, 0]));
-        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&vec![0, 4]));
+        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&[]));
+        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&[0]));
+        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&[0, 1]));
+        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&[0, 0]));
+        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&[0, 4]));
     }
 
     #[test]
     fn test_basic_safe()
     {
-        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&vec![1, 2, 3]));
-        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&vec![3, 2, 1]));
+        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&[1, 2, 3]));
+        assert_eq!(ReportSafety::Safe, SolverDay02::determine_safe(&[3, 2, 1]));
     }
 
     #[test]
     fn test_basic_unsafe()
     {
-        assert_eq!(ReportSafety::Unsafe, SolverDay02::determine_safe(&vec![1, 5, 9]));
-        assert_eq!(ReportSafety::Unsafe, SolverDay02::determine_safe(&vec![9, 5, 1]));
+        assert_eq!(ReportSafety::Unsafe, SolverDay02::determine_safe(&[1, 5, 9]));
+        assert_eq!(ReportSafety::Unsafe, SolverDay02::determine_safe(&[9, 5, 1]));
     }
 
     #[test]
     fn test_first_dampend()
     {
-        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&vec![1, 5, 6]));
-        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&vec![5, 1, 2]));
-        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&vec![1, 6, 5]));
-        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&vec![6, 2, 1]));
+        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&[1, 5, 6]));
+        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&[5, 1, 2]));
+        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&[1, 6, 5]));
+        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&[6, 2, 1]));
     }
 
     #[test]
     fn test_middle_dampend()
     {
-        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&vec![1, 6, 2]));
-        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&vec![5, 1, 6]));
-        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&vec![6, 1, 5]));
-        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&vec![2, 6, 1]));
+        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&[1, 6, 2]));
+        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&[5, 1, 6]));
+        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&[6, 1, 5]));
+        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&[2, 6, 1]));
     }
 
     #[test]
     fn test_last_dampend()
     {
-        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&vec![1, 2, 6]));
-        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&vec![5, 6, 1]));
-        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&vec![6, 5, 1]));
-        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&vec![2, 1, 6]));
+        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&[1, 2, 6]));
+        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&[5, 6, 1]));
+        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&[6, 5, 1]));
+        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&[2, 1, 6]));
     }
 
     #[test]
     fn test_ambiguous_direction_dampend()
     {
-        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&vec![6, 9, 6, 3]));
-        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&vec![6, 9, 3, 0]));
+        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&[6, 9, 6, 3]));
+        assert_eq!(ReportSafety::Dampened(1), SolverDay02::determine_safe(&[6, 9, 3, 0]));
     }
     
     #[test]
@@ -222,10 +416,132 @@ mod test
         assert_eq!(solution.part2, 4);
     }
 
+    #[test]
+    fn test_explain()
+    {
+        let sample: &str = "
+            7 6 4 2 1
+            1 2 7 8 9
+            1 3 2 4 5
+        ";
+
+        let options = ExplainOptions { part: 2, plain: true, ..Default::default() };
+        let explanation = SolverDay02::explain(Box::new(sample.split('\n')), &options).unwrap();
+
+        assert_eq!(explanation.lines().collect::<Vec<_>>(), vec![
+            "7 6 4 2 1                safe",
+            "1 2 7 8 9                unsafe, 2 -> 7 fails",
+            "1 3 2 4 5                dampened (removed level 3 at index 1), 3 -> 2 fails",
+            "Part 2: 2 safe reports",
+        ]);
+    }
+
+    // Compares with removing each level in turn, dampened index included, across reports from a fixed seed
+    #[test]
+    fn test_matches_brute_force()
+    {
+        let mut random = Random::new(0x2024_0002);
+
+        for _ in 0..20000
+        {
+            // Steps of -4..=4 give a good mix of safe, dampened and unsafe reports
+            let length = random.below(8);
+            let mut report = vec![random.below(20) as i32];
+            while report.len() < length
+            {
+                report.push(report[report.len() - 1] + random.between(-4, 4) as i32);
+            }
+            report.truncate(length);
+
+            assert_eq!(SolverDay02::determine_safe(&report), SolverDay02::brute_force_safety(&report), "{:?}", report);
+        }
+    }
+
+    #[test]
+    fn test_generate()
+    {
+        let options = GenerateOptions::new(2).with_knobs("reports=200,unsafe=0.25,dampened=0.5,min_levels=3,max_levels=12").unwrap();
+        let input = SolverDay02::generate(&options).unwrap();
+        assert_eq!(input.lines().count(), 200);
+        assert!(input.lines().all(|line| (3..=12).contains(&line.split(' ').count())));
+
+        let solution = SolverDay02::solve(Box::new(input.lines())).unwrap();
+        assert_eq!((solution.part1, solution.part2), (50, 150));
+
+        let options = GenerateOptions::new(2).with_knobs("unsafe=0.6,dampened=0.6").unwrap();
+        assert!(SolverDay02::generate(&options).is_err());
+    }
+
+    // Every set of removals, smallest first, in the order itertools gives combinations
+    fn exhaustive_removals(checker: &ReportChecker, report: &[i32]) -> Option<Vec<usize>>
+    {
+        (0..=checker.removals.min(report.len())).find_map(|count| {
+            (0..report.len()).combinations(count).find(|removed| {
+                let kept: Vec<i32> = (0..report.len()).filter(|idx| !removed.contains(idx)).map(|idx| report[idx]).collect();
+                checker.first_failure(&kept).is_none()
+            })
+        })
+    }
+
+    #[test]
+    fn test_removals()
+    {
+        let checker = ReportChecker { min_step: 1, max_step: 3, removals: 2 };
+        assert_eq!(checker.removals(&[]), Some(vec![]));
+        assert_eq!(checker.removals(&[1, 2, 3]), Some(vec![]));
+        assert_eq!(checker.removals(&[1, 9, 2, 9, 3]), Some(vec![1, 3]));
+        assert_eq!(checker.removals(&[1, 9, 9, 9, 3]), None);
+
+        let checker = ReportChecker { min_step: 0, max_step: 5, removals: 0 };
+        assert_eq!(checker.removals(&[3, 3, 8, 8]), Some(vec![]));
+        assert_eq!(checker.first_failure(&[3, 3, 8, 2]), Some(3));
+    }
+
+    #[test]
+    fn test_single_removal()
+    {
+        // Going down fails at index 1 and going up at index 3, and only splitting the later pair works
+        assert_eq!(DAMPENER.single_removal(&[1, 2, 3, 9, 4]), Some(3));
+        assert_eq!(DAMPENER.single_removal(&[5, 1, 2, 3]), Some(0));
+        assert_eq!(DAMPENER.single_removal(&[1, 5, 9, 13]), None);
+    }
+
+    #[test]
+    fn test_removals_match_exhaustive()
+    {
+        let mut random = Random::new(0x2024_0035);
+
+        for _ in 0..5000
+        {
+            let checker = ReportChecker {
+                min_step: random.between(0, 1) as i32,
+                max_step: random.between(2, 4) as i32,
+                removals: random.below(4)
+            };
+            let length = random.below(9);
+            let mut report = vec![random.below(20) as i32];
+            while report.len() < length
+            {
+                report.push(report[report.len() - 1] + random.between(-5, 5) as i32);
+            }
+            report.truncate(length);
+
+            assert_eq!(checker.removals(&report), exhaustive_removals(&checker, &report), "{:?} {:?}", checker, report);
+        }
+    }
+
     #[test]
     fn test_regression()
     {
         let values: Vec<i32> = "39 41 41 42 44 46 49 46".split_whitespace().map(|x| x.parse::<i32>().unwrap()).collect();
         assert_eq!(ReportSafety::Unsafe, SolverDay02::determine_safe(&values));
+
+        // Both of these were once reported unsafe, found by comparing with the brute force search
+        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&[16, 14, 15, 11]));
+        assert_eq!(ReportSafety::Dampened(2), SolverDay02::determine_safe(&[1, 2, 5, 3, 4]));
+
+        // Steps between levels this far apart once overflowed, found by fuzzing
+        assert_eq!(ReportSafety::Dampened(0), SolverDay02::determine_safe(&[i32::MIN, 1, 2]));
+        assert_eq!(ReportSafety::Unsafe, SolverDay02::determine_safe(&[i32::MAX, i32::MIN, i32::MAX]));
     }
 }
\ No newline at end of file
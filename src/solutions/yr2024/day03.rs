@@ -1,55 +1,294 @@
+use crate::generate::{GenerateOptions, Random};
+use crate::solver::{ExplainOptions, Solver, Solution, SolutionResult};
+use crate::visualise::{Colour, RESET};
 
-use crate::solver::{Solver, Solution, SolutionResult};
+/**
+ * Instructions that can appear in the corrupted memory. Adding one means giving it a name and
+ * operand count here, and saying what it does in Machine::execute
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Opcode
+{
+    Mul,
+    Do,
+    Dont
+}
 
-use regex::Regex;
+impl Opcode
+{
+    const ALL: [Opcode; 3] = [Opcode::Mul, Opcode::Do, Opcode::Dont];
 
-pub struct SolverDay03 {}
+    fn name(&self) -> &'static str
+    {
+        match self {
+            Opcode::Mul => "mul",
+            Opcode::Do => "do",
+            Opcode::Dont => "don't"
+        }
+    }
+
+    fn operand_count(&self) -> usize
+    {
+        match self {
+            Opcode::Mul => 2,
+            Opcode::Do | Opcode::Dont => 0
+        }
+    }
+}
 
-impl SolverDay03
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Instruction
 {
-    fn process_chunk(chunk: &str) -> isize
+    opcode: Opcode,
+    operands: Vec<isize>,
+    // Byte offset of the instruction in the memory it was read from
+    offset: usize,
+    length: usize
+}
+
+// Operands are 1-3 digit numbers, with no sign or whitespace
+fn parse_operand(text: &str) -> Option<isize>
+{
+    if text.is_empty() || text.len() > 3 || !text.bytes().all(|b| b.is_ascii_digit())
     {
-        let mul_matcher = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-        
-        mul_matcher.captures_iter(chunk).fold(0, |prod_sum, captures|
-        {
-            let lhs = captures[1].parse::<isize>().unwrap_or(0);
-            let rhs = captures[2].parse::<isize>().unwrap_or(0);
-            prod_sum + (lhs * rhs)
-        })
+        return None;
     }
+    text.parse().ok()
 }
 
-impl Solver for SolverDay03
+// The longest operands can be, as two 3 digit numbers and a comma, along with the closing bracket
+const MAX_OPERANDS_LENGTH: usize = 8;
+
+fn parse_instruction(memory: &str, offset: usize, opcode: Opcode) -> Option<Instruction>
 {
-    fn solve_impl<'a>(lines: Vec<&'a str>) -> SolutionResult
+    let operand_text = memory[offset..].strip_prefix(opcode.name())?.strip_prefix('(')?;
+    // Only as far as a valid instruction could need, so corruption like 'mul(mul(mul(' isn't read to the end every time
+    let close = operand_text.bytes().take(MAX_OPERANDS_LENGTH).position(|b| b == b')')?;
+    let operand_text = &operand_text[..close];
+
+    let operands = if operand_text.is_empty()
+    {
+        Vec::new()
+    }
+    else
+    {
+        operand_text.split(',').map(parse_operand).collect::<Option<Vec<isize>>>()?
+    };
+
+    if operands.len() != opcode.operand_count()
+    {
+        return None;
+    }
+
+    Some(Instruction { opcode, operands, offset, length: opcode.name().len() + close + 2 })
+}
+
+/**
+ * Reads the instructions out of the memory in order. Anything that isn't exactly a valid
+ * instruction is corruption, and scanning carries on from the next byte
+ */
+fn tokenize(memory: &str) -> Vec<Instruction>
+{
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+
+    while offset < memory.len()
     {
-        let mut result = Solution::default();
-        let full_input = lines.join("");
+        let instruction = Opcode::ALL.iter().find_map(|opcode| parse_instruction(memory, offset, *opcode));
+        match instruction {
+            Some(instruction) => {
+                offset += instruction.length;
+                instructions.push(instruction);
+            },
+            None => offset += memory[offset..].chars().next().map_or(1, char::len_utf8)
+        }
+    }
+
+    instructions
+}
 
-        let mut enabled = true;
+/**
+ * The memory as one string, joined from the lines once they are trimmed with blank ones left out,
+ * as other days have their lines cleaned. Where each line starts, both in the memory and in the
+ * file, is kept so that offsets can be given as they are in the file
+ */
+struct Memory
+{
+    text: String,
+    // Offsets in the memory and in the file of the start of each line's trimmed text
+    starts: Vec<(usize, usize)>
+}
 
-        for chunk in full_input.split("do")
+impl Memory
+{
+    // Each line is taken to end with a single '\n' in the file
+    fn new(lines: &[&str]) -> Self
+    {
+        let mut memory = Self { text: String::new(), starts: Vec::new() };
+        let mut file_offset = 0;
+        for line in lines
         {
-            if enabled && chunk.starts_with("n't()")
+            let trimmed = line.trim();
+            if !trimmed.is_empty()
             {
-                enabled = false;
-            }
-            if !enabled && chunk.starts_with("()")
-            {
-                enabled = true;
+                memory.starts.push((memory.text.len(), file_offset + line.len() - line.trim_start().len()));
+                memory.text.push_str(trimmed);
             }
+            file_offset += line.len() + 1;
+        }
+        memory
+    }
+
+    fn file_offset(&self, offset: usize) -> usize
+    {
+        let (start, file_start) = self.starts[self.starts.partition_point(|(start, _)| *start <= offset) - 1];
+        file_start + offset - start
+    }
+}
+
+// do() and don't() only have an effect with conditionals enabled, as in part 2
+struct Machine
+{
+    conditionals: bool,
+    enabled: bool,
+    total: isize
+}
+
+impl Machine
+{
+    fn new(conditionals: bool) -> Self
+    {
+        Self { conditionals, enabled: true, total: 0 }
+    }
+
+    // Returns whether the instruction was executed, rather than skipped
+    fn execute(&mut self, instruction: &Instruction) -> bool
+    {
+        match instruction.opcode {
+            Opcode::Mul if self.enabled => self.total += instruction.operands[0] * instruction.operands[1],
+            Opcode::Mul => return false,
+            Opcode::Do | Opcode::Dont if !self.conditionals => return false,
+            Opcode::Do => self.enabled = true,
+            Opcode::Dont => self.enabled = false
+        }
+        true
+    }
+
+    fn run(&mut self, instructions: &[Instruction]) -> isize
+    {
+        instructions.iter().for_each(|instruction| { self.execute(instruction); });
+        self.total
+    }
+}
 
-            let chunk_sum = Self::process_chunk(chunk);
-            
-            result.part1 += chunk_sum;
-            if enabled
+pub struct SolverDay03 {}
+
+// Characters that fill the space between instructions, taken from the puzzle's own corruption
+const JUNK: &[u8] = b"!@#$%^&*()[]{}<>,;:'+-/? whyhowselectfromwheremul";
+
+// Text that looks like an instruction but isn't one, to catch tokenizers that are too forgiving
+fn decoy(random: &mut Random) -> String
+{
+    let (a, b) = (random.between(1, 999), random.between(1, 999));
+    match random.below(10) {
+        0 => format!("mul({},{}", a, b),
+        1 => format!("mul({} ,{})", a, b),
+        2 => format!("mul[{},{}]", a, b),
+        3 => format!("mul({},{},{})", a, b, a),
+        4 => format!("mul({},{})", random.between(1000, 9999), b),
+        5 => format!("mul ({},{})", a, b),
+        6 => format!("MUL({},{})", a, b),
+        7 => format!("mul(-{},{})", a, b),
+        8 => "don't".to_string(),
+        _ => format!("do({})", a)
+    }
+}
+
+impl Solver for SolverDay03
+{
+    // Memory does the cleaning, as it needs to know where each line started in the file
+    fn clean<'a>(lines: Box<dyn Iterator<Item = &'a str> + 'a>) -> Vec<&'a str>
+    {
+        lines.collect()
+    }
+
+    fn solve_impl(lines: Vec<&str>) -> SolutionResult
+    {
+        // The memory is one long string, so an instruction can carry on over a line break
+        let instructions = tokenize(&Memory::new(&lines).text);
+
+        Ok(Solution {
+            part1: Machine::new(false).run(&instructions),
+            part2: Machine::new(true).run(&instructions)
+        })
+    }
+
+    /**
+     * Lines of corrupted memory of about 'length' characters. Each piece of it is a real mul with
+     * chance 'muls', a do() or don't() with chance 'switches', something that only looks like an
+     * instruction with chance 'decoys', and otherwise a junk character
+     */
+    fn generate(options: &GenerateOptions) -> anyhow::Result<String>
+    {
+        let lines: usize = options.knob("lines", 6)?;
+        let length: usize = options.knob("length", 3000)?;
+        let muls = options.ratio("muls", 0.08)?;
+        let switches = options.ratio("switches", 0.01)?;
+        let decoys = options.ratio("decoys", 0.05)?;
+
+        let mut random = options.random();
+        let mut input = String::new();
+        for _ in 0..lines
+        {
+            let mut line = String::new();
+            while line.len() < length
             {
-                result.part2 += chunk_sum
+                if random.chance(muls)
+                {
+                    line.push_str(&format!("mul({},{})", random.between(1, 999), random.between(1, 999)));
+                }
+                else if random.chance(switches)
+                {
+                    line.push_str(if random.chance(0.5) { "do()" } else { "don't()" });
+                }
+                else if random.chance(decoys)
+                {
+                    line.push_str(&decoy(&mut random));
+                }
+                else
+                {
+                    line.push(*random.choose(JUNK) as char);
+                }
             }
+            input.push_str(&line);
+            input.push('\n');
         }
+        Ok(input)
+    }
+
+    /**
+     * Traces every instruction found, with its byte offset in the input file, whether it was
+     * executed or skipped, and the running total
+     */
+    fn explain_impl(lines: Vec<&str>, options: &ExplainOptions) -> anyhow::Result<String>
+    {
+        let memory = Memory::new(&lines);
+        let mut machine = Machine::new(options.part == 2);
+        let mut trace = format!("{:>8}  {:<14} {:<9} {}\n", "offset", "instruction", "", "total");
 
-        Ok(result)
+        for instruction in tokenize(&memory.text)
+        {
+            let executed = machine.execute(&instruction);
+            let text = &memory.text[instruction.offset..instruction.offset + instruction.length];
+            let (verdict, colour) = if executed { ("executed", Colour::Green) } else { ("skipped", Colour::Red) };
+            let verdict = format!("{:<9}", verdict);
+            let verdict = if options.plain { verdict } else { format!("{}{}{}", colour.escape(), verdict, RESET) };
+
+            trace.push_str(&format!("{:>8}  {:<14} {} {}\n", memory.file_offset(instruction.offset), text, verdict, machine.total));
+        }
+
+        trace.push_str(&format!("Part {}: {}", options.part, machine.total));
+        Ok(trace)
     }
 }
 
@@ -58,13 +297,97 @@ mod test
 {
     use super::*;
 
+    #[test]
+    fn test_tokenize()
+    {
+        let instructions = tokenize("mul(1,23)mul(123,4)mul(1234,5)mul(,1)mul(1,2,3)mul ( 2,3)mul(-1,2)do()don't()do(1)xdon't()");
+
+        let found: Vec<(Opcode, Vec<isize>, usize)> = instructions.into_iter()
+            .map(|instruction| (instruction.opcode, instruction.operands, instruction.offset))
+            .collect();
+        assert_eq!(found, vec![
+            (Opcode::Mul, vec![1, 23], 0),
+            (Opcode::Mul, vec![123, 4], 9),
+            (Opcode::Do, vec![], 66),
+            (Opcode::Dont, vec![], 70),
+            (Opcode::Dont, vec![], 83),
+        ]);
+    }
+
+    #[test]
+    fn test_tokenize_resumes_inside_corruption()
+    {
+        let instructions = tokenize("mul(mul(2,3)é)do(don't()");
+        let opcodes: Vec<Opcode> = instructions.iter().map(|instruction| instruction.opcode).collect();
+        assert_eq!(opcodes, vec![Opcode::Mul, Opcode::Dont]);
+        assert_eq!(instructions[0].offset, 4);
+    }
+
+    #[test]
+    fn test_tokenize_unclosed()
+    {
+        // Looking for the ')' through the rest of the memory made this quadratic
+        assert!(tokenize(&"mul(".repeat(100_000)).is_empty());
+        assert!(tokenize("mul(1,2     )").is_empty());
+    }
+
+    #[test]
+    fn test_generate()
+    {
+        let options = GenerateOptions::new(3).with_knobs("lines=2,length=500,muls=0,switches=0,decoys=1").unwrap();
+        let input = SolverDay03::generate(&options).unwrap();
+        assert_eq!(input.lines().count(), 2);
+        assert!(input.lines().all(|line| line.len() >= 500));
+        let solution = SolverDay03::solve(Box::new(input.lines())).unwrap();
+        assert_eq!((solution.part1, solution.part2), (0, 0));
+
+        let options = GenerateOptions::new(3).with_knobs("muls=0.5,switches=0").unwrap();
+        let solution = SolverDay03::solve(Box::new(SolverDay03::generate(&options).unwrap().lines())).unwrap();
+        assert!(solution.part1 > 0);
+        assert_eq!(solution.part1, solution.part2);
+    }
+
+    #[test]
+    fn test_trace()
+    {
+        let sample = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5)";
+        let options = ExplainOptions { part: 2, plain: true, ..Default::default() };
+        let trace = SolverDay03::explain(Box::new(sample.split('\n')), &options).unwrap();
+
+        assert_eq!(trace.lines().collect::<Vec<_>>(), vec![
+            "  offset  instruction              total",
+            "       1  mul(2,4)       executed  8",
+            "      20  don't()        executed  8",
+            "      28  mul(5,5)       skipped   8",
+            "      48  mul(11,8)      skipped   8",
+            "      59  do()           executed  8",
+            "      64  mul(8,5)       executed  48",
+            "Part 2: 48",
+        ]);
+    }
+
+    #[test]
+    fn test_trace_file_offsets()
+    {
+        // Offsets count the indentation, line breaks and blank lines that aren't part of the memory
+        let options = ExplainOptions { part: 1, plain: true, ..Default::default() };
+        let trace = SolverDay03::explain(Box::new("  mul(1,2)\n\n  xmul(3,\n4)".split('\n')), &options).unwrap();
+
+        assert_eq!(trace.lines().collect::<Vec<_>>(), vec![
+            "  offset  instruction              total",
+            "       2  mul(1,2)       executed  2",
+            "      15  mul(3,4)       executed  14",
+            "Part 1: 14",
+        ]);
+    }
+
     #[test]
     fn test_sample_part1()
     {
         let sample: &str = "
 xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))
         ";
-        
+
         let solution = SolverDay03::solve(Box::new(sample.split('\n'))).unwrap();
         assert_eq!(solution.part1, 161);
     }
@@ -75,8 +398,16 @@ xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))
         let sample: &str = "
 xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5)
         ";
-        
+
         let solution = SolverDay03::solve(Box::new(sample.split('\n'))).unwrap();
         assert_eq!(solution.part2, 48);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_across_lines()
+    {
+        // The lines are one memory with nothing between them, so a split instruction still counts
+        let solution = SolverDay03::solve(Box::new(["mul(2,", "3)don", "'t()mul(4,5)"].into_iter())).unwrap();
+        assert_eq!((solution.part1, solution.part2), (26, 6));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, slice::Iter};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::hash::Hash;
 
@@ -7,7 +7,9 @@ use itertools::Itertools;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-use crate::solver::{Solver, Solution, SolutionResult};
+use crate::generate::{GenerateOptions, Random};
+use crate::solver::{ExplainOptions, Solver, Solution, SolutionResult};
+use crate::visualise::{Colour, Flow, Frame, FrameSink, Grid};
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
 struct Point
@@ -35,6 +37,32 @@ impl Direction
     {
         i8::abs((*self as i8) - (*other as i8)) % 4 == 2
     }
+
+    // Turned clockwise by the given number of eighths
+    fn rotated(&self, eighths: usize) -> Direction
+    {
+        Direction::iter().nth((*self as usize + eighths) % 8).unwrap()
+    }
+
+    fn opposite(&self) -> Direction
+    {
+        self.rotated(4)
+    }
+
+    // Change in x and y for one step in this direction
+    fn offset(&self) -> (isize, isize)
+    {
+        match self {
+            Direction::N  => (0, -1),
+            Direction::NE => (1, -1),
+            Direction::E  => (1, 0),
+            Direction::SE => (1, 1),
+            Direction::S  => (0, 1),
+            Direction::SW => (-1, 1),
+            Direction::W  => (-1, 0),
+            Direction::NW => (-1, -1),
+        }
+    }
 }
 
 impl Point
@@ -52,55 +80,347 @@ impl Point
             Direction::NE => Some(Point { x: self.x.checked_add(count)?, y: self.y.checked_sub(count)? }),
         }
     }
+
+    // The points covered by a word of the given length starting here
+    fn line(&self, direction: &Direction, length: usize) -> Option<Vec<Point>>
+    {
+        (0..length).map(|count| self.get_relative(direction, count)).collect()
+    }
+
+    // The points covered by a word of the given length centred here
+    fn centred_line(&self, direction: &Direction, length: usize) -> Option<Vec<Point>>
+    {
+        self.get_relative(&direction.opposite(), length >> 1)?.line(direction, length)
+    }
 }
 
 
-struct LetterGrid<'a>
+/**
+ * Aho-Corasick automaton over a dictionary, so a single pass along a line of letters finds
+ * every word of the dictionary in it
+ */
+struct WordAutomaton
 {
-    grid: Vec<&'a str>
+    transitions: Vec<HashMap<char, usize>>,
+    fail: Vec<usize>,
+    // Indices of the words ending at each state, including those reached through fail links
+    outputs: Vec<Vec<usize>>,
+    lengths: Vec<usize>
 }
 
-impl<'a> LetterGrid<'a>
+impl WordAutomaton
 {
-    fn new(grid: Vec<&'a str>) -> Self
+    fn new(words: &[&str]) -> Self
     {
-        Self { grid }
+        let mut automaton = Self {
+            transitions: vec![HashMap::new()],
+            fail: vec![0],
+            outputs: vec![Vec::new()],
+            lengths: words.iter().map(|word| word.chars().count()).collect()
+        };
+
+        // Build the trie
+        for (word_idx, word) in words.iter().enumerate()
+        {
+            let mut state = 0;
+            for c in word.chars()
+            {
+                state = match automaton.transitions[state].get(&c) {
+                    Some(next) => *next,
+                    None => {
+                        automaton.transitions.push(HashMap::new());
+                        automaton.fail.push(0);
+                        automaton.outputs.push(Vec::new());
+                        let next = automaton.transitions.len() - 1;
+                        automaton.transitions[state].insert(c, next);
+                        next
+                    }
+                };
+            }
+            if !word.is_empty()
+            {
+                automaton.outputs[state].push(word_idx);
+            }
+        }
+
+        // Breadth first, so each state's fail link is complete before its children need it
+        let mut queue: std::collections::VecDeque<usize> = automaton.transitions[0].values().copied().collect();
+        while let Some(state) = queue.pop_front()
+        {
+            let children: Vec<(char, usize)> = automaton.transitions[state].iter().map(|(c, next)| (*c, *next)).collect();
+            for (c, child) in children
+            {
+                let child_fail = automaton.next_state(automaton.fail[state], c);
+                automaton.fail[child] = child_fail;
+                let inherited = automaton.outputs[child_fail].clone();
+                automaton.outputs[child].extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        automaton
     }
 
-    fn iter(&'a self) -> Iter<'a, &'a str>
+    fn next_state(&self, mut state: usize, c: char) -> usize
     {
-        self.grid.iter()
+        loop
+        {
+            if let Some(next) = self.transitions[state].get(&c)
+            {
+                return *next;
+            }
+            if state == 0
+            {
+                return 0;
+            }
+            state = self.fail[state];
+        }
     }
+}
 
-    fn get(&'a self, pos: &Point)  -> Option<char>
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Overlap
+{
+    // Every match is returned, even if it shares letters with another
+    Allowed,
+    // Matches are taken in reading order of their first letter, skipping any that share a letter with one already taken
+    Disjoint
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WordMatch
+{
+    word: usize,
+    start: Point,
+    direction: Direction,
+    cells: Vec<Point>
+}
+
+// A word laid out in a straight line, with one of its letters on the anchor of a shape
+#[derive(Debug, Clone)]
+struct Arm
+{
+    word: String,
+    direction: Direction,
+    anchor_index: usize
+}
+
+/**
+ * A template of words that must all be present around a single anchor letter. Reversible
+ * shapes also match with any arm read backwards, and rotatable shapes in each of the four
+ * right angle rotations
+ */
+#[derive(Debug, Clone)]
+struct Shape
+{
+    arms: Vec<Arm>,
+    reversible: bool,
+    rotatable: bool
+}
+
+impl Shape
+{
+    fn new(arms: Vec<Arm>, reversible: bool, rotatable: bool) -> Self
     {
-        Some(self.grid.get(pos.y)?.chars().nth(pos.x)?)
+        Self { arms, reversible, rotatable }
     }
 
-    fn find_all_char(&'a self, needle: &char) -> Vec<Point>
+    // The word twice, crossing diagonally at its middle letter
+    fn x(word: &str) -> Self
     {
-        self.iter().enumerate().fold(Vec::<Point>::new(), |mut points, (row, s)| {
-            points.extend(s.match_indices(*needle).map(|(idx, _)| Point { x: idx, y: row}));
-            points
-        })
+        let centre = word.chars().count() >> 1;
+        Self::new(vec![
+            Arm { word: word.to_string(), direction: Direction::SE, anchor_index: centre },
+            Arm { word: word.to_string(), direction: Direction::SW, anchor_index: centre },
+        ], true, false)
+    }
+
+    // The word twice, crossing horizontally and vertically at its middle letter
+    fn plus(word: &str) -> Self
+    {
+        let centre = word.chars().count() >> 1;
+        Self::new(vec![
+            Arm { word: word.to_string(), direction: Direction::E, anchor_index: centre },
+            Arm { word: word.to_string(), direction: Direction::S, anchor_index: centre },
+        ], true, false)
+    }
+
+    // The word twice at a right angle, sharing its first letter at the corner
+    fn l(word: &str) -> Self
+    {
+        Self::new(vec![
+            Arm { word: word.to_string(), direction: Direction::E, anchor_index: 0 },
+            Arm { word: word.to_string(), direction: Direction::S, anchor_index: 0 },
+        ], false, true)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ShapeMatch
+{
+    anchor: Point,
+    // Sorted in reading order, without duplicates
+    cells: Vec<Point>
+}
+
+fn reading_order(point: &Point) -> (usize, usize)
+{
+    (point.y, point.x)
+}
+
+// Keeps matches that do not share a cell with an earlier one
+fn disjoint<T>(matches: Vec<T>, cells: impl Fn(&T) -> &[Point]) -> Vec<T>
+{
+    let mut used = HashSet::new();
+    matches.into_iter().filter(|m| {
+        let free = cells(m).iter().all(|cell| !used.contains(cell));
+        if free
+        {
+            used.extend(cells(m).iter().copied());
+        }
+        free
+    }).collect()
+}
+
+
+// Marks the cells past the end of a row that is shorter than the widest one
+const PADDING: char = '\0';
+
+/**
+ * Letters are stored row after row in one buffer, so any letter can be looked up directly.
+ * Grids made only of ASCII are stored as bytes, which searches for ASCII words can compare
+ * without decoding
+ */
+enum Letters
+{
+    Ascii(Vec<u8>),
+    Unicode(Vec<char>)
+}
+
+struct LetterGrid
+{
+    letters: Letters,
+    width: usize,
+    height: usize
+}
+
+impl LetterGrid
+{
+    fn new(grid: Vec<&str>) -> Self
+    {
+        let width = grid.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        let height = grid.len();
+
+        let letters = if grid.iter().all(|row| row.is_ascii())
+        {
+            let mut bytes = vec![PADDING as u8; width * height];
+            for (y, row) in grid.iter().enumerate()
+            {
+                bytes[y * width..y * width + row.len()].copy_from_slice(row.as_bytes());
+            }
+            Letters::Ascii(bytes)
+        }
+        else
+        {
+            let mut chars = vec![PADDING; width * height];
+            for (y, row) in grid.iter().enumerate()
+            {
+                for (x, c) in row.chars().enumerate()
+                {
+                    chars[y * width + x] = c;
+                }
+            }
+            Letters::Unicode(chars)
+        };
+
+        Self { letters, width, height }
+    }
+
+    fn flat(&self, pos: &Point) -> Option<usize>
+    {
+        (pos.x < self.width && pos.y < self.height).then(|| pos.y * self.width + pos.x)
+    }
+
+    fn unflat(&self, idx: usize) -> Point
+    {
+        Point { x: idx % self.width, y: idx / self.width }
+    }
+
+    fn letter(&self, idx: usize) -> char
+    {
+        match &self.letters {
+            Letters::Ascii(bytes) => bytes[idx] as char,
+            Letters::Unicode(chars) => chars[idx]
+        }
+    }
+
+    fn get(&self, pos: &Point)  -> Option<char>
+    {
+        Some(self.letter(self.flat(pos)?)).filter(|c| *c != PADDING)
+    }
+
+    fn find_all_char(&self, needle: &char) -> Vec<Point>
+    {
+        (0..self.width * self.height).filter(|idx| self.letter(*idx) == *needle).map(|idx| self.unflat(idx)).collect()
     }
 
     fn find_all_word(&self, word: &str) -> Vec<(Point, Direction)>
     {
+        if let (Letters::Ascii(bytes), true) = (&self.letters, word.is_ascii())
+        {
+            return self.find_all_ascii_word(bytes, word.as_bytes());
+        }
+
         let candidate_origins = self.find_all_char( &word.chars().nth(0).unwrap());
 
         candidate_origins.iter().cartesian_product(Direction::iter()).filter_map(|(origin, direction)| {
-            let mut next_point = origin.clone();
+            let mut next_point = *origin;
 
-            for c in word[1..].chars()
+            for c in word.chars().skip(1)
             {
                 next_point = next_point.get_relative(&direction,1)?;
                 self.get(&next_point).filter(|n| *n == c)?;
             }
-            Some((origin.clone(), direction.clone()))
+            Some((*origin, direction))
         }).collect()
     }
 
+    /**
+     * As find_all_word, comparing bytes. Checking the far end of the word is inside the grid
+     * first means every letter can then be reached with a fixed stride through the buffer
+     */
+    fn find_all_ascii_word(&self, bytes: &[u8], word: &[u8]) -> Vec<(Point, Direction)>
+    {
+        let mut found = Vec::new();
+        let Some((first, rest)) = word.split_first() else {
+            return found;
+        };
+
+        for (idx, _) in bytes.iter().enumerate().filter(|(_, byte)| *byte == first)
+        {
+            let origin = self.unflat(idx);
+            for direction in Direction::iter()
+            {
+                if origin.get_relative(&direction, rest.len()).and_then(|end| self.flat(&end)).is_none()
+                {
+                    continue;
+                }
+
+                let (dx, dy) = direction.offset();
+                let stride = dy * self.width as isize + dx;
+                let matches = rest.iter().enumerate().all(|(count, byte)| {
+                    bytes[(idx as isize + stride * (count as isize + 1)) as usize] == *byte
+                });
+                if matches
+                {
+                    found.push((origin, direction));
+                }
+            }
+        }
+
+        found
+    }
+
     fn find_all_x_word(&self, word: &str) -> Vec<(Point, (Direction, Direction))>
     {
         let words = self.find_all_word(word);
@@ -110,7 +430,7 @@ impl<'a> LetterGrid<'a>
             if let Direction::NE | Direction::SE | Direction::SW | Direction::NW = *direction
             {
                 let center = start.get_relative(direction, center_dist).unwrap();
-                map.entry(center).or_insert_with(|| Vec::new()).push(direction.clone());
+                map.entry(center).or_insert_with(Vec::new).push(*direction);
             }
             map
         });
@@ -130,31 +450,357 @@ impl<'a> LetterGrid<'a>
 
         results
     }
+
+    // The points covered by each XMAS found for part 1, or each X-MAS crossing for part 2
+    fn matches(&self, part: u8) -> Vec<Vec<Point>>
+    {
+        if part == 1
+        {
+            let word = "XMAS";
+            self.find_all_word(word).iter()
+                .filter_map(|(start, direction)| start.line(direction, word.len()))
+                .collect()
+        }
+        else
+        {
+            let x_word = "MAS";
+            self.find_all_x_word(x_word).iter()
+                .filter_map(|(center, (first, second))| {
+                    let mut points = center.centred_line(first, x_word.len())?;
+                    points.extend(center.centred_line(second, x_word.len())?);
+                    Some(points)
+                })
+                .collect()
+        }
+    }
 }
 
-impl<'a> fmt::Display for LetterGrid<'a>
+// Searching for many words, or shapes of words, in one pass over the grid
+impl LetterGrid
+{
+    // Every point in the grid, in reading order
+    fn points(&self) -> impl Iterator<Item = Point> + '_
+    {
+        (0..self.width * self.height).filter(|idx| self.letter(*idx) != PADDING).map(|idx| self.unflat(idx))
+    }
+
+    // The line of points starting at the given point, up to the edge of the grid
+    fn line_from(&self, start: Point, direction: &Direction) -> Vec<Point>
+    {
+        std::iter::successors(Some(start), |point| point.get_relative(direction, 1))
+            .take_while(|point| self.get(point).is_some())
+            .collect()
+    }
+
+    /**
+     * Finds every word of the dictionary in every direction. Each line of the grid is read once
+     * per direction, from the edge it starts at, through an automaton of the whole dictionary
+     */
+    fn find_words(&self, dictionary: &[&str], overlap: Overlap) -> Vec<WordMatch>
+    {
+        let automaton = WordAutomaton::new(dictionary);
+        let mut matches = Vec::new();
+
+        for direction in Direction::iter()
+        {
+            let line_starts = self.points().filter(|point| {
+                point.get_relative(&direction.opposite(), 1).and_then(|previous| self.get(&previous)).is_none()
+            });
+
+            for line_start in line_starts
+            {
+                let line = self.line_from(line_start, &direction);
+                let mut state = 0;
+                for (idx, point) in line.iter().enumerate()
+                {
+                    state = automaton.next_state(state, self.get(point).unwrap_or_default());
+                    for word in automaton.outputs[state].iter()
+                    {
+                        let cells = line[idx + 1 - automaton.lengths[*word]..=idx].to_vec();
+                        matches.push(WordMatch { word: *word, start: cells[0], direction, cells });
+                    }
+                }
+            }
+        }
+
+        matches.sort_by_key(|m| (reading_order(&m.start), m.direction as u8, m.word));
+        match overlap {
+            Overlap::Allowed => matches,
+            Overlap::Disjoint => disjoint(matches, |m| &m.cells)
+        }
+    }
+
+    // The cells of an arm placed on the anchor, if it fits in the grid and reads as its word
+    fn match_arm(&self, anchor: &Point, arm: &Arm, direction: &Direction, reversible: bool) -> Option<Vec<Point>>
+    {
+        let cells = anchor.get_relative(&direction.opposite(), arm.anchor_index)?.line(direction, arm.word.chars().count())?;
+        let letters = cells.iter().map(|cell| self.get(cell)).collect::<Option<String>>()?;
+
+        if letters == arm.word || (reversible && letters.chars().rev().eq(arm.word.chars()))
+        {
+            return Some(cells);
+        }
+        None
+    }
+
+    /**
+     * Finds every placement of the shape, trying each rotation of it on each letter of the grid.
+     * Placements covering the same cells, such as a symmetric shape rotated, are only returned once
+     */
+    fn find_shapes(&self, shape: &Shape, overlap: Overlap) -> Vec<ShapeMatch>
+    {
+        let rotations = if shape.rotatable { 4 } else { 1 };
+        let mut seen = HashSet::new();
+        let mut matches = Vec::new();
+
+        for anchor in self.points()
+        {
+            for rotation in 0..rotations
+            {
+                let arm_cells: Option<Vec<Vec<Point>>> = shape.arms.iter()
+                    .map(|arm| self.match_arm(&anchor, arm, &arm.direction.rotated(rotation * 2), shape.reversible))
+                    .collect();
+
+                if let Some(arm_cells) = arm_cells
+                {
+                    let cells: Vec<Point> = arm_cells.into_iter().flatten().unique().sorted_by_key(reading_order).collect();
+                    if seen.insert(cells.clone())
+                    {
+                        matches.push(ShapeMatch { anchor, cells });
+                    }
+                }
+            }
+        }
+
+        match overlap {
+            Overlap::Allowed => matches,
+            Overlap::Disjoint => disjoint(matches, |m| &m.cells)
+        }
+    }
+
+    /**
+     * Runs a search written as a comma separated list of words, or as 'x:WORD', 'plus:WORD' or
+     * 'l:WORD' for a shape, returning the points covered by each match. Following it with
+     * 'disjoint' skips matches that share letters with one found before, as in 'l:XMAS disjoint'
+     */
+    fn query(&self, query: &str) -> anyhow::Result<Vec<Vec<Point>>>
+    {
+        match query.split_whitespace().collect::<Vec<&str>>()[..] {
+            [search] => self.search(search, Overlap::Allowed),
+            [search, "disjoint"] => self.search(search, Overlap::Disjoint),
+            _ => anyhow::bail!("Query '{}' should be a search, optionally followed by 'disjoint'", query)
+        }
+    }
+
+    // The search part of a query, see LetterGrid::query
+    fn search(&self, query: &str, overlap: Overlap) -> anyhow::Result<Vec<Vec<Point>>>
+    {
+        let shape = match query.split_once(':') {
+            None => {
+                let dictionary: Vec<&str> = query.split(',').filter(|word| !word.is_empty()).collect();
+                return Ok(self.find_words(&dictionary, overlap).into_iter().map(|m| m.cells).collect());
+            },
+            Some(("x", word)) => Shape::x(word),
+            Some(("plus", word)) => Shape::plus(word),
+            Some(("l", word)) => Shape::l(word),
+            Some((name, _)) => anyhow::bail!("Unknown shape '{}', expected x, plus or l", name)
+        };
+        Ok(self.find_shapes(&shape, overlap).into_iter().map(|m| m.cells).collect())
+    }
+}
+
+impl fmt::Display for LetterGrid
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.iter().join("\n"))
+        let mut rows = (0..self.height).map(|y| {
+            (0..self.width).map(|x| self.letter(y * self.width + x)).filter(|c| *c != PADDING).collect::<String>()
+        });
+        write!(f, "{}", rows.join("\n"))
     }
 }
 
 pub struct SolverDay04 {}
 
+// Letters placed so far while generating, with None where the filler will go
+struct Planting
+{
+    cells: Vec<Vec<Option<char>>>,
+    attempts: usize
+}
+
+impl Planting
+{
+    fn fits(&self, points: &[Point], letters: &[char]) -> bool
+    {
+        points.iter().zip(letters).all(|(point, letter)| {
+            self.cells.get(point.y).and_then(|row| row.get(point.x)).is_some_and(|cell| cell.is_none() || *cell == Some(*letter))
+        })
+    }
+
+    fn place(&mut self, points: &[Point], letters: &[char])
+    {
+        for (point, letter) in points.iter().zip(letters)
+        {
+            self.cells[point.y][point.x] = Some(*letter);
+        }
+    }
+
+    // Tries random places until one fits, where 'lines' gives the points for a place, if it has them
+    fn plant(&mut self, random: &mut Random, letters: &[Vec<char>], lines: impl Fn(&Point, &mut Random) -> Option<Vec<Vec<Point>>>) -> bool
+    {
+        for _ in 0..self.attempts
+        {
+            let start = Point { x: random.below(self.cells[0].len()), y: random.below(self.cells.len()) };
+            let Some(lines) = lines(&start, random) else {
+                continue;
+            };
+            if lines.iter().zip(letters).all(|(points, letters)| self.fits(points, letters))
+            {
+                lines.iter().zip(letters).for_each(|(points, letters)| self.place(points, letters));
+                return true;
+            }
+        }
+        false
+    }
+}
+
 impl Solver for SolverDay04
 {
-    fn solve_impl<'a>(lines: Vec<&'a str>) -> SolutionResult
+    fn solve_impl(lines: Vec<&str>) -> SolutionResult
     {
+        // The single word searches, rather than find_words and find_shapes, so ASCII grids are searched as bytes
         let wordsearch = LetterGrid::new(lines);
         let word = "XMAS";
        
-        let mut result = Solution::default();
-        result.part1 = wordsearch.find_all_word(word).len() as isize;
-
         let x_word = "MAS";
-        result.part2 = wordsearch.find_all_x_word(x_word).len() as isize;
-        
-        Ok(result)
+
+        Ok(Solution {
+            part1: wordsearch.find_all_word(word).len() as isize,
+            part2: wordsearch.find_all_x_word(x_word).len() as isize
+        })
+    }
+
+    /**
+     * Shows the grid with every matched letter coloured, or in plain mode blanks out every other
+     * letter with '.', as the puzzle does in its examples. A query searches for other words or
+     * shapes instead, see LetterGrid::query
+     */
+    fn explain_impl(lines: Vec<&str>, options: &ExplainOptions) -> anyhow::Result<String>
+    {
+        let wordsearch = LetterGrid::new(lines);
+        let found = match &options.query {
+            Some(query) => wordsearch.query(query)?,
+            None => wordsearch.matches(options.part)
+        };
+        let matched: HashSet<Point> = found.iter().flatten().copied().collect();
+
+        let mut grid = Grid::from_display(&wordsearch);
+        for y in 0..grid.height()
+        {
+            for x in 0..grid.width()
+            {
+                let is_match = matched.contains(&Point { x, y });
+                if let Some(cell) = grid.get_mut(x, y)
+                {
+                    match (is_match, options.plain) {
+                        (true, false) => cell.colour = Some(Colour::Green),
+                        (false, true) => cell.symbol = '.',
+                        _ => ()
+                    }
+                }
+            }
+        }
+
+        Ok(format!("{}\n{} matches covering {} letters", grid, found.len(), matched.len()))
+    }
+
+    /**
+     * A 'width' by 'height' grid with 'words' copies of 'word' planted in any direction and
+     * 'crosses' pairs of MAS crossing, the rest filled from 'letters'. The filler makes its own
+     * matches too, unless it is something like '.'
+     */
+    fn generate(options: &GenerateOptions) -> anyhow::Result<String>
+    {
+        let width: usize = options.knob("width", 140)?;
+        let height: usize = options.knob("height", 140)?;
+        let words: usize = options.knob("words", 100)?;
+        let crosses: usize = options.knob("crosses", 50)?;
+        let word: String = options.knob("word", "XMAS".to_string())?;
+        let filler: Vec<char> = options.knob("letters", "XMAS".to_string())?.chars().collect();
+        if width == 0 || height == 0 || word.is_empty() || filler.is_empty()
+        {
+            anyhow::bail!("The grid, word and letters can't be empty");
+        }
+
+        let mut random = options.random();
+        let mut planting = Planting { cells: vec![vec![None; width]; height], attempts: 10 * width * height };
+
+        let word: Vec<char> = word.chars().collect();
+        let directions: Vec<Direction> = Direction::iter().collect();
+        for _ in 0..words
+        {
+            let planted = planting.plant(&mut random, std::slice::from_ref(&word), |start, random| {
+                Some(vec![start.line(random.choose(&directions), word.len())?])
+            });
+            if !planted
+            {
+                anyhow::bail!("Only room for some of the {} words, try a bigger grid", words);
+            }
+        }
+
+        // Either way along each diagonal
+        let mas: Vec<char> = "MAS".chars().collect();
+        let sam: Vec<char> = "SAM".chars().collect();
+        for _ in 0..crosses
+        {
+            let letters = [if random.chance(0.5) { mas.clone() } else { sam.clone() }, if random.chance(0.5) { mas.clone() } else { sam.clone() }];
+            let planted = planting.plant(&mut random, &letters, |centre, _| {
+                Some(vec![centre.centred_line(&Direction::SE, 3)?, centre.centred_line(&Direction::NE, 3)?])
+            });
+            if !planted
+            {
+                anyhow::bail!("Only room for some of the {} crosses, try a bigger grid", crosses);
+            }
+        }
+
+        let mut input = String::new();
+        for row in planting.cells
+        {
+            input.extend(row.iter().map(|cell| cell.unwrap_or_else(|| *random.choose(&filler))));
+            input.push('\n');
+        }
+        Ok(input)
+    }
+
+    // Highlights each word found in turn, then all of them together. Part 1 finds XMAS, part 2 the crossing MAS pairs
+    fn visualise_impl(lines: Vec<&str>, part: u8, sink: &mut dyn FrameSink) -> anyhow::Result<()>
+    {
+        let wordsearch = LetterGrid::new(lines);
+        let found = wordsearch.matches(part);
+
+        for (count, points) in found.iter().enumerate()
+        {
+            let mut grid = Grid::from_display(&wordsearch);
+            for point in points
+            {
+                grid.set_colour(point.x, point.y, Colour::Green);
+            }
+
+            let caption = format!("Part {} | match {} of {} at ({}, {})", part, count + 1, found.len(), points[0].x, points[0].y);
+            if sink.push(Frame::grid(caption, grid))? == Flow::Quit
+            {
+                return Ok(());
+            }
+        }
+
+        let mut grid = Grid::from_display(&wordsearch);
+        for point in found.iter().flatten()
+        {
+            grid.set_colour(point.x, point.y, Colour::Green);
+        }
+        sink.push(Frame::grid(format!("Part {} | all {} matches", part, found.len()), grid))?;
+
+        Ok(())
     }
 }
 
@@ -184,13 +830,22 @@ mod test
         assert_eq!(origin.get_relative(&Direction::NE, 1), None);
     }
 
+    #[test]
+    fn test_point_line()
+    {
+        let start = Point { x: 1, y: 1 };
+        assert_eq!(start.line(&Direction::SE, 3), Some(vec![Point { x: 1, y: 1 }, Point { x: 2, y: 2 }, Point { x: 3, y: 3 }]));
+        assert_eq!(start.line(&Direction::W, 3), None);
+        assert_eq!(start.centred_line(&Direction::NE, 3), Some(vec![Point { x: 0, y: 2 }, Point { x: 1, y: 1 }, Point { x: 2, y: 0 }]));
+    }
+
     #[test]
     fn test_point_is_perp()
     {
-        assert_eq!(Direction::N.is_perp(&Direction::E), true);
-        assert_eq!(Direction::N.is_perp(&Direction::W), true);
-        assert_eq!(Direction::N.is_perp(&Direction::NE), false);
-        assert_eq!(Direction::N.is_perp(&Direction::NW), false);
+        assert!(Direction::N.is_perp(&Direction::E));
+        assert!(Direction::N.is_perp(&Direction::W));
+        assert!(!Direction::N.is_perp(&Direction::NE));
+        assert!(!Direction::N.is_perp(&Direction::NW));
     }
 
     #[test]
@@ -214,6 +869,22 @@ mod test
         assert_eq!(input.get(&Point { x: 3, y: 3 }), None);
     }
 
+    #[test]
+    fn test_grid_uneven_and_unicode()
+    {
+        let uneven = LetterGrid::new(vec!["AB", "C", "DEF"]);
+        assert_eq!(uneven.get(&Point { x: 1, y: 1 }), None);
+        assert_eq!(uneven.get(&Point { x: 2, y: 2 }), Some('F'));
+        assert_eq!(uneven.to_string(), "AB\nC\nDEF");
+        assert_eq!(uneven.find_all_word("BE"), vec![]);
+
+        let unicode = LetterGrid::new(vec!["ÄB", "CÖ"]);
+        assert_eq!(unicode.get(&Point { x: 1, y: 1 }), Some('Ö'));
+        assert_eq!(unicode.find_all_char(&'Ö'), vec![Point { x: 1, y: 1 }]);
+        assert_eq!(unicode.find_all_word("ÄÖ"), vec![(Point { x: 0, y: 0 }, Direction::SE)]);
+        assert_eq!(unicode.to_string(), "ÄB\nCÖ");
+    }
+
     #[test]
     fn test_find_all_word()
     {
@@ -278,10 +949,7 @@ mod test
     }
 
 
-    #[test]
-    fn test_sample()
-    {
-        let sample: &str = "
+    const SAMPLE: &str = "
 MMMSXXMASM
 MSAMXMSMSA
 AMXSXMAAMM
@@ -294,9 +962,162 @@ MAMMMXMMMM
 MXMXAXMASX
         ";
 
-        let solution = SolverDay04::solve(Box::new(sample.split('\n'))).unwrap();
+    #[test]
+    fn test_word_automaton()
+    {
+        let automaton = WordAutomaton::new(&["he", "she", "his", "hers"]);
+        let mut found = Vec::new();
+        let mut state = 0;
+        for (idx, c) in "ushers".chars().enumerate()
+        {
+            state = automaton.next_state(state, c);
+            found.extend(automaton.outputs[state].iter().map(|word| (idx, *word)));
+        }
+        found.sort();
+        assert_eq!(found, vec![(3, 0), (3, 1), (5, 3)]);
+    }
+
+    #[test]
+    fn test_find_words_matches_find_all_word()
+    {
+        let grid = LetterGrid::new(SAMPLE.split_whitespace().collect());
+
+        for word in ["XMAS", "MAS", "SAM", "X"]
+        {
+            let expected = grid.find_all_word(word);
+            let found: Vec<(Point, Direction)> = grid.find_words(&[word], Overlap::Allowed).iter()
+                .map(|m| (m.start, m.direction))
+                .collect();
+            assert_eq!(found, expected, "{}", word);
+        }
+
+        let together = grid.find_words(&["XMAS", "MAS", "SAM"], Overlap::Allowed);
+        assert_eq!(together.len(), grid.find_all_word("XMAS").len() + 2 * grid.find_all_word("MAS").len());
+    }
+
+    #[test]
+    fn test_find_words_disjoint()
+    {
+        let grid = LetterGrid::new(vec!["ABA"]);
+        assert_eq!(grid.find_words(&["AB", "BA"], Overlap::Allowed).len(), 4);
+
+        let disjoint = grid.find_words(&["AB", "BA"], Overlap::Disjoint);
+        assert_eq!(disjoint, vec![WordMatch {
+            word: 0,
+            start: Point { x: 0, y: 0 },
+            direction: Direction::E,
+            cells: vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }]
+        }]);
+    }
+
+    #[test]
+    fn test_find_shapes()
+    {
+        let grid = LetterGrid::new(SAMPLE.split_whitespace().collect());
+        assert_eq!(grid.find_shapes(&Shape::x("MAS"), Overlap::Allowed).len(), grid.find_all_x_word("MAS").len());
+
+        let plus = LetterGrid::new(vec![
+            ".M.",
+            "MAS",
+            ".S."
+        ]);
+        assert_eq!(plus.find_shapes(&Shape::plus("MAS"), Overlap::Allowed), vec![ShapeMatch {
+            anchor: Point { x: 1, y: 1 },
+            cells: vec![Point { x: 1, y: 0 }, Point { x: 0, y: 1 }, Point { x: 1, y: 1 }, Point { x: 2, y: 1 }, Point { x: 1, y: 2 }]
+        }]);
+        assert_eq!(plus.find_shapes(&Shape::x("MAS"), Overlap::Allowed), vec![]);
+
+        let l = LetterGrid::new(vec![
+            "..C",
+            "..B",
+            "CBA",
+            "B..",
+            "C.."
+        ]);
+        let corners: Vec<Point> = l.find_shapes(&Shape::l("ABC"), Overlap::Allowed).iter().map(|m| m.anchor).collect();
+        assert_eq!(corners, vec![Point { x: 2, y: 2 }]);
+
+        let square = LetterGrid::new(vec![
+            "ABA",
+            "B.B",
+            "ABA"
+        ]);
+        assert_eq!(square.find_shapes(&Shape::l("AB"), Overlap::Allowed).len(), 4);
+        assert_eq!(square.find_shapes(&Shape::l("ABA"), Overlap::Allowed).len(), 4);
+        assert_eq!(square.find_shapes(&Shape::l("ABA"), Overlap::Disjoint).len(), 1);
+    }
+
+    #[test]
+    fn test_search_query()
+    {
+        let grid = LetterGrid::new(SAMPLE.split_whitespace().collect());
+        assert_eq!(grid.search("XMAS", Overlap::Allowed).unwrap().len(), 18);
+        assert_eq!(grid.search("x:MAS", Overlap::Allowed).unwrap().len(), 9);
+        assert!(grid.search("star:MAS", Overlap::Allowed).is_err());
+
+        assert_eq!(grid.query("XMAS").unwrap().len(), 18);
+        assert!(grid.query("XMAS,SAMX disjoint").unwrap().len() < 18);
+        assert_eq!(grid.query("XMAS overlapping").unwrap_err().to_string(), "Query 'XMAS overlapping' should be a search, optionally followed by 'disjoint'");
+        assert!(grid.query("").is_err());
+    }
+
+    #[test]
+    fn test_explain_plain()
+    {
+        let part1 = SolverDay04::explain(Box::new(SAMPLE.split('\n')), &ExplainOptions { part: 1, plain: true, ..Default::default() }).unwrap();
+        assert_eq!(part1.lines().take(10).collect::<Vec<_>>(), vec![
+            "....XXMAS.",
+            ".SAMXMS...",
+            "...S..A...",
+            "..A.A.MS.X",
+            "XMASAMX.MM",
+            "X.....XA.A",
+            "S.S.S.S.SS",
+            ".A.A.A.A.A",
+            "..M.M.M.MM",
+            ".X.X.XMASX",
+        ]);
+
+        let part2 = SolverDay04::explain(Box::new(SAMPLE.split('\n')), &ExplainOptions { part: 2, plain: true, ..Default::default() }).unwrap();
+        assert_eq!(part2.lines().take(10).collect::<Vec<_>>(), vec![
+            ".M.S......",
+            "..A..MSMS.",
+            ".M.S.MAA..",
+            "..A.ASMSM.",
+            ".M.S.M....",
+            "..........",
+            "S.S.S.S.S.",
+            ".A.A.A.A..",
+            "M.M.M.M.M.",
+            "..........",
+        ]);
+        assert_eq!(part2.lines().last(), Some("9 matches covering 35 letters"));
+    }
+
+    #[test]
+    fn test_generate()
+    {
+        let options = GenerateOptions::new(4).with_knobs("width=30,height=20,words=6,crosses=4,letters=.").unwrap();
+        let input = SolverDay04::generate(&options).unwrap();
+        assert_eq!(input.lines().count(), 20);
+        assert!(input.lines().all(|line| line.len() == 30));
+        let solution = SolverDay04::solve(Box::new(input.lines())).unwrap();
+        assert_eq!((solution.part1, solution.part2), (6, 4));
+
+        let options = GenerateOptions::new(4).with_knobs("width=3,height=3,words=1").unwrap();
+        assert!(SolverDay04::generate(&options).is_err());
+    }
+
+    #[test]
+    fn test_sample()
+    {
+        let solution = SolverDay04::solve(Box::new(SAMPLE.split('\n'))).unwrap();
         assert_eq!(solution.part1, 18);
         assert_eq!(solution.part2, 9);
-    }
 
-}
\ No newline at end of file
+        // The general searches that queries use find the same
+        let grid = LetterGrid::new(SAMPLE.split_whitespace().collect());
+        assert_eq!(grid.find_words(&["XMAS"], Overlap::Allowed).len(), 18);
+        assert_eq!(grid.find_shapes(&Shape::x("MAS"), Overlap::Allowed).len(), 9);
+    }
+}
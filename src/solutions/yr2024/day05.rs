@@ -1,5 +1,6 @@
-use std::{cmp::Ordering, collections::{HashMap, HashSet}};
+use std::{collections::{HashMap, HashSet}, fmt};
 
+use crate::generate::GenerateOptions;
 use crate::solver::{Solver, Solution, SolutionResult};
 
 #[derive(Default)]
@@ -19,89 +20,192 @@ enum RuleResult {
     ReOrdered(isize)
 }
 
+// Reasons the rules don't give a single correct order for an update
+#[derive(Debug, PartialEq, Eq)]
+enum RuleError {
+    Empty,
+    Repeated(isize),
+    // Each page must come before the next, and the last before the first
+    Cycle(Vec<isize>),
+    // Nothing decides which of these two pages comes first
+    NotUnique(isize, isize)
+}
+
+impl fmt::Display for RuleError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            RuleError::Empty => write!(f, "update has no pages"),
+            RuleError::Repeated(page) => write!(f, "page {} appears more than once", page),
+            RuleError::Cycle(pages) => {
+                let cycle: Vec<String> = pages.iter().chain(pages.first()).map(|page| page.to_string()).collect();
+                write!(f, "rules form a cycle {}", cycle.join(" -> "))
+            },
+            RuleError::NotUnique(first, second) => write!(f, "no rule orders pages {} and {}, so the correct order is not unique", first, second)
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
 impl RuleChecker
 {
     // A 'Rule' implies first must appear only before second if both are present
     fn add_rule(&mut self, first: isize, second: isize)
     {
-        self.rules.entry(first).or_insert(Rule::default()).prohibited_before.insert(second);
-        self.rules.entry(second).or_insert(Rule::default()).prohibited_after.insert(first);
+        self.rules.entry(first).or_default().prohibited_before.insert(second);
+        self.rules.entry(second).or_default().prohibited_after.insert(first);
     }
 
-    // Recursive call adds the prohibited list 
-    fn check_rec(&self, update_slice: &[isize], prohibited: HashSet<isize>) -> Option<isize>
+    fn must_precede(&self, first: &isize, second: &isize) -> bool
     {
-        let pivot_idx = update_slice.len()/2;
-        let pivot =  update_slice[pivot_idx];
+        self.rules.get(first).is_some_and(|rule| rule.prohibited_before.contains(second))
+    }
 
-        if prohibited.contains(&pivot)
+    /**
+     * Follows rules backwards from the first page until a page comes round again. Only called
+     * when every page has another page that must come before it, so there is always a way back
+     */
+    fn find_cycle(&self, pages: &[isize]) -> Vec<isize>
+    {
+        let mut path = vec![pages[0]];
+        loop
         {
-            return None;
+            let page = path[path.len() - 1];
+            let before = *pages.iter().find(|other| self.must_precede(other, &page)).unwrap();
+            if let Some(start) = path.iter().position(|visited| *visited == before)
+            {
+                let mut cycle = path.split_off(start);
+                cycle.reverse();
+                return cycle;
+            }
+            path.push(before);
         }
+    }
 
-        let left = &update_slice[..pivot_idx];
-        let mut left_prohibited = prohibited.clone();
-
-        let right = &update_slice[pivot_idx+1..];
-        let mut right_prohibited = prohibited;
-        
-        if let Some(pivot_rule) = self.rules.get(&pivot)
+    /**
+     * A cycle in the rules between the update's pages, if there is one. Pages with nothing left
+     * before them are taken away until none are, and each page still left then has another before
+     * it, which is what find_cycle needs
+     */
+    fn cycle_in(&self, update: &[isize]) -> Option<Vec<isize>>
+    {
+        let mut remaining = update.to_vec();
+        loop
         {
-            left_prohibited.extend(&pivot_rule.prohibited_before);
-            right_prohibited.extend(&pivot_rule.prohibited_after);
-        }
+            let blocked: Vec<isize> = remaining.iter()
+                .filter(|page| remaining.iter().any(|other| self.must_precede(other, page)))
+                .copied()
+                .collect();
 
-        if (left.len() == 0 || self.check_rec(left, left_prohibited).is_some()) &&
-            (right.len() == 0 || self.check_rec(right, right_prohibited).is_some())
+            if blocked.is_empty()
+            {
+                return None;
+            }
+            if blocked.len() == remaining.len()
             {
-                return Some(pivot);
+                return Some(self.find_cycle(&remaining));
             }
-        None
+            remaining = blocked;
+        }
     }
 
-    // Search the provided string as a tree, rooted at the middle element. Returns the middle element
-    fn check(&self, update: &Vec<isize>) -> RuleResult
+    // An update needs some pages to have a middle one, and each page only once to have an order
+    fn validate(update: &[isize]) -> Result<(), RuleError>
     {
-        if let Some(middle_val) = self.check_rec(update, HashSet::new())
+        if update.is_empty()
         {
-            return RuleResult::Correct(middle_val);
+            return Err(RuleError::Empty);
         }
+        let mut seen = HashSet::new();
+        match update.iter().find(|page| !seen.insert(**page)) {
+            Some(page) => Err(RuleError::Repeated(*page)),
+            None => Ok(())
+        }
+    }
 
-        let re_ordered = self.correct(&update);
-        RuleResult::ReOrdered(re_ordered[re_ordered.len()/2])
+    // No page comes after a page that a rule says it must come before
+    fn keeps_rules(&self, update: &[isize]) -> bool
+    {
+        update.iter().enumerate().all(|(idx, page)| !update[idx + 1..].iter().any(|later| self.must_precede(later, page)))
     }
 
-    // Comparison function used to correct ordering
-    fn get_order(&self, left: &isize, right: &isize) -> Ordering
+    /**
+     * The only order of the update's pages that keeps to the rules between them. Pages are
+     * placed one at a time, each being the only one left with no remaining page before it. A
+     * cycle is looked for first, as it would otherwise be hidden behind pages that aren't ordered
+     */
+    fn order(&self, update: &[isize]) -> Result<Vec<isize>, RuleError>
     {
-        if let Some(pivot_rule) = self.rules.get(&left)
+        Self::validate(update)?;
+        if let Some(cycle) = self.cycle_in(update)
         {
-            if pivot_rule.prohibited_before.contains(&right)
-            {
-                return Ordering::Less;
-            }
-            else if pivot_rule.prohibited_after.contains(&right)
-            {
-                return Ordering::Greater;
+            return Err(RuleError::Cycle(cycle));
+        }
+
+        let mut remaining = update.to_vec();
+        let mut ordered = Vec::with_capacity(update.len());
+
+        while !remaining.is_empty()
+        {
+            let first: Vec<isize> = remaining.iter()
+                .filter(|page| !remaining.iter().any(|other| self.must_precede(other, page)))
+                .copied()
+                .collect();
+
+            match first[..] {
+                [page] => {
+                    ordered.push(page);
+                    remaining.retain(|other| *other != page);
+                },
+                [a, b, ..] => return Err(RuleError::NotUnique(a, b)),
+                // Without a cycle, some remaining page always has nothing before it
+                [] => unreachable!("no cycle in {:?}, yet every page has one before it", remaining)
             }
         }
-        Ordering::Equal
+
+        Ok(ordered)
     }
 
-    // Use the rules to fix an incorrectly ordered update
-    fn correct(&self, update: &Vec<isize>) -> Vec<isize>
+    /**
+     * Returns the middle page of the update, once it is in the correct order. An update that
+     * already keeps to the rules is correct as it is, even if other orders would too, so only
+     * updates that need reordering can fail for want of a single correct order
+     */
+    fn check(&self, update: &[isize]) -> Result<RuleResult, RuleError>
     {
-        let mut re_ordered = update.clone();
-        re_ordered.sort_by(|x, y| self.get_order(x, y));
-        re_ordered
+        Self::validate(update)?;
+        if self.keeps_rules(update)
+        {
+            return Ok(RuleResult::Correct(update[update.len()/2]));
+        }
+
+        let ordered = self.order(update)?;
+        Ok(RuleResult::ReOrdered(ordered[ordered.len()/2]))
     }
 }
 
 pub struct SolverDay05 {}
 
+fn parse_pages(text: &str, separator: char) -> anyhow::Result<Vec<isize>>
+{
+    text.split(separator)
+        .map(|page| page.trim().parse::<isize>().map_err(|_| anyhow::anyhow!("'{}' is not a page number in '{}'", page, text)))
+        .collect()
+}
+
+fn parse_rule(line: &str) -> anyhow::Result<(isize, isize)>
+{
+    match parse_pages(line, '|')?[..] {
+        [first, second] => Ok((first, second)),
+        _ => anyhow::bail!("Rule '{}' should be two pages", line)
+    }
+}
+
 impl Solver for SolverDay05
 {
-    fn solve_impl<'a>(lines: Vec<&'a str>) -> SolutionResult
+    fn solve_impl(lines: Vec<&str>) -> SolutionResult
     {
         let mut result = Solution::default();
 
@@ -111,23 +215,134 @@ impl Solver for SolverDay05
         {
             if line.contains('|')
             {
-                let rule_parts: Vec<isize> = line.split('|').map(|x| x.parse::<isize>().unwrap()).collect();
-                rule_checker.add_rule(rule_parts[0], rule_parts[1]);
-                continue;              
+                let (first, second) = parse_rule(line)?;
+                rule_checker.add_rule(first, second);
+                continue;
             }
 
-            let update_parts: Vec<isize> = line.split(',').map(|x| x.parse::<isize>().unwrap()).collect();
+            let update_parts = parse_pages(line, ',')?;
 
             // Sums middle elements depending on if ordering was correct
-            match rule_checker.check(&update_parts)
+            let (total, value) = match rule_checker.check(&update_parts).map_err(|e| anyhow::anyhow!("Update {}: {}", line, e))?
             {
-                RuleResult::Correct(value) => result.part1 += value,
-                RuleResult::ReOrdered(value) => result.part2 += value
-            }
+                RuleResult::Correct(value) => (&mut result.part1, value),
+                RuleResult::ReOrdered(value) => (&mut result.part2, value)
+            };
+            *total = total.checked_add(value).ok_or_else(|| anyhow::anyhow!("Middle pages add up to more than can be counted"))?;
         }
 
         Ok(result)
     }
+
+    /**
+     * Rules giving every pair of 'pages' pages a fixed order, so every update has exactly one
+     * correct order, then 'updates' updates of 'min_length' to 'max_length' pages. The 'sorted'
+     * proportion of updates are already in order
+     */
+    fn generate(options: &GenerateOptions) -> anyhow::Result<String>
+    {
+        let page_count: usize = options.knob("pages", 49)?;
+        let updates: usize = options.knob("updates", 200)?;
+        let min_length: usize = options.knob("min_length", 5)?;
+        let max_length: usize = options.knob("max_length", 23)?;
+        let sorted_count = (options.ratio("sorted", 0.5)? * updates as f64).round() as usize;
+        if min_length == 0 || min_length > max_length || max_length > page_count
+        {
+            anyhow::bail!("Updates need 1 to 'pages' pages, and min_length can't be more than max_length");
+        }
+
+        // Two digit page numbers like the puzzle's, unless there are too many pages for that
+        let mut random = options.random();
+        let mut numbers: Vec<isize> = (10..10 + (90.max(2 * page_count)) as isize).collect();
+        random.shuffle(&mut numbers);
+        let order: Vec<isize> = numbers[..page_count].to_vec();
+        let rank: HashMap<isize, usize> = order.iter().enumerate().map(|(rank, page)| (*page, rank)).collect();
+
+        let mut rules: Vec<String> = (0..page_count)
+            .flat_map(|first| (first + 1..page_count).map(move |second| (first, second)))
+            .map(|(first, second)| format!("{}|{}", order[first], order[second]))
+            .collect();
+        random.shuffle(&mut rules);
+
+        let mut in_order: Vec<bool> = (0..updates).map(|index| index < sorted_count).collect();
+        random.shuffle(&mut in_order);
+
+        let mut input = rules.join("\n");
+        input.push_str("\n\n");
+        for in_order in in_order
+        {
+            let length = random.between(min_length as isize, max_length as isize) as usize;
+            let mut update = order.clone();
+            random.shuffle(&mut update);
+            update.truncate(length);
+
+            let mut sorted = update.clone();
+            sorted.sort_by_key(|page| rank[page]);
+            if in_order
+            {
+                update = sorted;
+            }
+            else
+            {
+                // Single pages are always in order, so one is left as it is
+                while update == sorted && length > 1
+                {
+                    random.shuffle(&mut update);
+                }
+            }
+
+            let pages: Vec<String> = update.iter().map(|page| page.to_string()).collect();
+            input.push_str(&pages.join(","));
+            input.push('\n');
+        }
+        Ok(input)
+    }
+
+    // The rules as a graph from each page to the pages that must come after it, then each update
+    fn inspect_impl(lines: Vec<&str>) -> anyhow::Result<String>
+    {
+        let mut rule_checker = RuleChecker::default();
+        let mut updates = Vec::new();
+        for line in lines
+        {
+            if line.contains('|')
+            {
+                let (first, second) = parse_rule(line)?;
+                rule_checker.add_rule(first, second);
+            }
+            else
+            {
+                updates.push(parse_pages(line, ',')?);
+            }
+        }
+
+        let mut pages: Vec<&isize> = rule_checker.rules.keys().collect();
+        pages.sort();
+        let rule_count: usize = rule_checker.rules.values().map(|rule| rule.prohibited_before.len()).sum();
+
+        let mut text = format!("{} rules between {} pages\n", rule_count, pages.len());
+        for page in pages
+        {
+            let mut after: Vec<&isize> = rule_checker.rules[page].prohibited_before.iter().collect();
+            after.sort();
+            let after: Vec<String> = after.iter().map(|page| page.to_string()).collect();
+            text.push_str(&format!("  {:>3} -> {}\n", page, if after.is_empty() { "nothing".to_string() } else { after.join(" ") }));
+        }
+
+        text.push_str(&format!("{} updates\n", updates.len()));
+        for update in updates
+        {
+            let pages: Vec<String> = update.iter().map(|page| page.to_string()).collect();
+            let result = match rule_checker.check(&update) {
+                Ok(RuleResult::Correct(middle)) => format!("in order, middle page {}", middle),
+                Ok(RuleResult::ReOrdered(middle)) => format!("out of order, middle page {} once sorted", middle),
+                Err(e) => e.to_string()
+            };
+            text.push_str(&format!("  {}  {}\n", pages.join(","), result));
+        }
+
+        Ok(text.trim_end().to_string())
+    }
 }
 
 #[cfg(test)]
@@ -138,13 +353,108 @@ mod test
     #[test]
     fn test_sort()
     {
-        let list: Vec<isize> = vec![1, 2, 3, 4];
+        let mut rule_checker = RuleChecker::default();
+
+        rule_checker.add_rule(2, 1);
+        rule_checker.add_rule(1, 3);
 
+        assert_eq!(rule_checker.order(&[1, 2, 3]), Ok(vec![2, 1, 3]));
+        assert_eq!(rule_checker.order(&[3, 1]), Ok(vec![1, 3]));
+    }
+
+    #[test]
+    fn test_not_unique()
+    {
         let mut rule_checker = RuleChecker::default();
 
         rule_checker.add_rule(2, 1);
 
-        assert_eq!(rule_checker.correct(&list), vec![2, 1, 3, 4]);
+        assert_eq!(rule_checker.order(&[1, 2, 3, 4]), Err(RuleError::NotUnique(2, 3)));
+    }
+
+    #[test]
+    fn test_cycle()
+    {
+        let mut rule_checker = RuleChecker::default();
+
+        rule_checker.add_rule(1, 2);
+        rule_checker.add_rule(2, 3);
+        rule_checker.add_rule(3, 1);
+        rule_checker.add_rule(4, 1);
+
+        // Only pages in the update count, so without 3 there is no cycle
+        assert_eq!(rule_checker.order(&[2, 4, 1]), Ok(vec![4, 1, 2]));
+
+        let error = rule_checker.order(&[4, 3, 2, 1]).unwrap_err();
+        assert_eq!(error, RuleError::Cycle(vec![1, 2, 3]));
+        assert_eq!(error.to_string(), "rules form a cycle 1 -> 2 -> 3 -> 1");
+    }
+
+    #[test]
+    fn test_cycle_among_unordered()
+    {
+        let mut rule_checker = RuleChecker::default();
+
+        rule_checker.add_rule(1, 2);
+        rule_checker.add_rule(2, 3);
+        rule_checker.add_rule(3, 1);
+
+        // 5 and 6 have nothing before them, which would be reported as not unique before the cycle is reached
+        let error = rule_checker.order(&[5, 6, 1, 2, 3]).unwrap_err();
+        assert_eq!(error.to_string(), "rules form a cycle 2 -> 3 -> 1 -> 2");
+
+        let error = SolverDay05::solve(Box::new(["1|2", "2|3", "3|1", "5,6,1,2,3"].into_iter())).unwrap_err();
+        assert_eq!(error.to_string(), "Update 5,6,1,2,3: rules form a cycle 2 -> 3 -> 1 -> 2");
+    }
+
+    #[test]
+    fn test_invalid_updates()
+    {
+        let rule_checker = RuleChecker::default();
+
+        assert_eq!(rule_checker.order(&[]), Err(RuleError::Empty));
+        assert_eq!(rule_checker.order(&[5, 6, 5]), Err(RuleError::Repeated(5)));
+
+        // Nothing orders 3, but the update already keeps to the rules, so it counts for part 1
+        let solution = SolverDay05::solve(Box::new(["1|2", "1,2,3"].into_iter())).unwrap();
+        assert_eq!((solution.part1, solution.part2), (2, 0));
+
+        let error = SolverDay05::solve(Box::new(["1|2", "2,1,3"].into_iter())).unwrap_err();
+        assert_eq!(error.to_string(), "Update 2,1,3: no rule orders pages 1 and 3, so the correct order is not unique");
+        assert!(SolverDay05::solve(Box::new(["1,2,1"].into_iter())).is_err());
+
+        let error = SolverDay05::solve(Box::new(["9223372036854775807", "1"].into_iter())).unwrap_err();
+        assert_eq!(error.to_string(), "Middle pages add up to more than can be counted");
+        assert!(SolverDay05::solve(Box::new(["1|", "1"].into_iter())).is_err());
+    }
+
+    #[test]
+    fn test_inspect()
+    {
+        let text = SolverDay05::inspect(Box::new(["47|53", "97|47", "97|53", "", "97,47,53", "53,47"].into_iter())).unwrap();
+        assert_eq!(text, "3 rules between 3 pages
+   47 -> 53
+   53 -> nothing
+   97 -> 47 53
+2 updates
+  97,47,53  in order, middle page 47
+  53,47  out of order, middle page 53 once sorted");
+    }
+
+    #[test]
+    fn test_generate()
+    {
+        let options = GenerateOptions::new(5).with_knobs("pages=10,updates=30,min_length=3,max_length=9,sorted=1").unwrap();
+        let input = SolverDay05::generate(&options).unwrap();
+        assert_eq!(input.lines().filter(|line| line.contains('|')).count(), 45);
+        assert_eq!(input.lines().filter(|line| line.contains(',')).count(), 30);
+        assert_eq!(SolverDay05::solve(Box::new(input.lines())).unwrap().part2, 0);
+
+        let options = GenerateOptions::new(5).with_knobs("pages=120,min_length=2,sorted=0").unwrap();
+        let input = SolverDay05::generate(&options).unwrap();
+        assert_eq!(SolverDay05::solve(Box::new(input.lines())).unwrap().part1, 0);
+
+        assert!(SolverDay05::generate(&GenerateOptions::new(5).with_knobs("pages=4").unwrap()).is_err());
     }
 
     #[test]
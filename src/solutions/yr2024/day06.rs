@@ -1,7 +1,9 @@
 use std::{fmt, hash::Hash};
 use std::collections::HashSet;
 
+use crate::generate::GenerateOptions;
 use crate::solver::{Solver, Solution, SolutionResult};
+use crate::visualise::{Colour, Flow, Frame, FrameSink, Grid};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 enum Direction {
@@ -37,18 +39,22 @@ impl Direction
     {
         self.right().right()
     }
-    
-}
 
-impl fmt::Display for Direction {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!( f, "{}", match self
+    fn symbol(&self) -> char
+    {
+        match self
         {
             Direction::S => 'V',
             Direction::E => '>',
             Direction::N => '^',
             Direction::W => '<',
-        })
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!( f, "{}", self.symbol())
     }
 }
 
@@ -58,6 +64,7 @@ enum TileState {
     Obstacle
 }
 
+#[derive(Clone)]
 pub struct Guard {
     position: (isize, isize),
     direction: Direction,
@@ -87,12 +94,6 @@ impl Guard
         self.position = (self.position.0 + direction_parts.0, self.position.1 + direction_parts.1);
     }
     
-    fn peek_right(&self) -> (isize, isize)
-    {
-        let direction_parts = self.direction.right().get_parts();
-        (self.position.0 + direction_parts.0, self.position.1 + direction_parts.1)
-    }
-    
     fn rotate(&mut self)
     {
         self.direction = self.direction.right();
@@ -125,33 +126,43 @@ pub struct Map {
     width: usize,
     height: usize,
     visited: isize,
-    loop_insert_count: isize
+    looped: bool,
+    obstruction: Option<(isize, isize)>
 }
 
 impl Map {
-    fn new<'a>(lines: Vec<&'a str>) -> Self
+    // Rows must all be as wide as the first, with one guard among the clear tiles and obstacles
+    fn new(lines: Vec<&str>) -> anyhow::Result<Self>
     {
-        let width = lines[0].len();
+        let width = lines.first().map_or(0, |line| line.len());
         let height = lines.len();
 
         let mut tiles = vec![TileState::Clear(HashSet::new()); width * height];
 
-        let mut guard_pos: (isize, isize) = (0, 0);
+        let mut guard_pos: Option<(isize, isize)> = None;
 
         for (y, row) in lines.iter().enumerate()
         {
+            if row.len() != width
+            {
+                anyhow::bail!("Row {} is {} wide, but the first row is {}", y + 1, row.len(), width);
+            }
+
             for (x, tile) in row.chars().enumerate()
             {
                 match tile
                 {
                     '#' => tiles[(y * width ) + x] = TileState::Obstacle,
-                    '^' => guard_pos = (x as isize, y as isize),
-                    _ => continue
+                    '^' if guard_pos.is_some() => anyhow::bail!("There is more than one guard, the second at ({}, {})", x, y),
+                    '^' => guard_pos = Some((x as isize, y as isize)),
+                    '.' => continue,
+                    _ => anyhow::bail!("Unexpected '{}' at ({}, {}), tiles are '.', '#' or '^'", tile, x, y)
                 }
             }
         }
 
-        Self {tiles, guard: Guard::new(guard_pos), width, height, visited: 0, loop_insert_count: 0}
+        let guard_pos = guard_pos.ok_or_else(|| anyhow::anyhow!("There is no guard '^' on the map"))?;
+        Ok(Self {tiles, guard: Guard::new(guard_pos), width, height, visited: 0, looped: false, obstruction: None})
     }
 
     fn flat(&self, pos: (isize, isize)) -> Option<isize>
@@ -171,43 +182,164 @@ impl Map {
     {
         ((idx % self.width) as isize, (idx / self.width) as isize)
     }
+
+    // The tile in front of the guard, if it is clear and has not been walked yet
+    fn unvisited_ahead(&self) -> Option<(isize, isize)>
+    {
+        if let TileState::Obstacle = self.tiles[self.flat(self.guard.position)? as usize]
+        {
+            return None;
+        }
+
+        let ahead = self.guard.peek_forward();
+        match self.tiles[self.flat(ahead)? as usize]
+        {
+            TileState::Clear(ref directions) if directions.is_empty() => Some(ahead),
+            _ => None
+        }
+    }
+
+    /**
+     * Copy of the map without any walk history, with an extra obstacle placed and the guard
+     * starting from the given state
+     */
+    fn with_obstruction(&self, obstruction: (isize, isize), guard: &Guard) -> Self
+    {
+        let mut tiles: Vec<TileState> = self.tiles.iter().map(|tile| match tile {
+            TileState::Clear(_) => TileState::Clear(HashSet::new()),
+            TileState::Obstacle => TileState::Obstacle
+        }).collect();
+
+        if let Some(flat_pos) = self.flat(obstruction)
+        {
+            tiles[flat_pos as usize] = TileState::Obstacle;
+        }
+
+        Self {
+            tiles,
+            guard: guard.clone(),
+            width: self.width,
+            height: self.height,
+            visited: 0,
+            looped: false,
+            obstruction: Some(obstruction)
+        }
+    }
+
+    // Walks the map to the end, returning the start of every obstructed walk worth trying
+    fn obstruction_candidates(&mut self) -> Vec<((isize, isize), Guard)>
+    {
+        let mut candidates = Vec::new();
+        loop
+        {
+            if let Some(ahead) = self.unvisited_ahead()
+            {
+                candidates.push((ahead, self.guard.clone()));
+            }
+            if self.next().is_none()
+            {
+                return candidates;
+            }
+        }
+    }
+
+    fn guard_on_obstacle(&self) -> bool
+    {
+        matches!(self.flat(self.guard.position).map(|idx| &self.tiles[idx as usize]), Some(TileState::Obstacle))
+    }
+
+    // Tiles making up the loop the guard is stuck in, found by walking once round from where it was detected
+    fn loop_tiles(&self) -> HashSet<(isize, isize)>
+    {
+        let Some(obstruction) = self.obstruction.filter(|_| self.looped) else {
+            return HashSet::new();
+        };
+
+        let mut lap = self.with_obstruction(obstruction, &self.guard);
+        let mut tiles: HashSet<(isize, isize)> = lap.by_ref().collect();
+        tiles.retain(|pos| matches!(self.flat(*pos).map(|idx| &self.tiles[idx as usize]), Some(TileState::Clear(_))));
+        tiles
+    }
+
+    /**
+     * Walked tiles are drawn as X, unless an obstruction has been placed, in which case they are
+     * drawn the way the puzzle draws loops: | and - for the headings taken, + where they cross
+     */
+    fn tile_symbol(&self, idx: usize) -> char
+    {
+        let pos = self.unflat(idx);
+        if pos == self.guard.position
+        {
+            return self.guard.direction.symbol();
+        }
+        if Some(pos) == self.obstruction
+        {
+            return 'O';
+        }
+
+        match &self.tiles[idx] {
+            TileState::Obstacle => '#',
+            TileState::Clear(directions) if directions.is_empty() => '.',
+            TileState::Clear(_) if self.obstruction.is_none() => 'X',
+            TileState::Clear(directions) => {
+                let vertical = directions.contains(&Direction::N) || directions.contains(&Direction::S);
+                let horizontal = directions.contains(&Direction::E) || directions.contains(&Direction::W);
+                match (vertical, horizontal) {
+                    (true, true) => '+',
+                    (true, false) => '|',
+                    _ => '-'
+                }
+            }
+        }
+    }
+
+    // Frame of the map with the obstruction and the given tiles highlighted
+    fn highlighted_frame(&self, caption: String, highlight: &HashSet<(isize, isize)>) -> Frame
+    {
+        let mut grid = Grid::from_display(self);
+        for (x, y) in highlight.iter().chain(self.obstruction.iter())
+        {
+            grid.set_colour(*x as usize, *y as usize, Colour::Yellow);
+        }
+        Frame::grid(caption, grid)
+    }
+
+    fn loops(&self, obstruction: (isize, isize), guard: &Guard) -> bool
+    {
+        let mut obstructed = self.with_obstruction(obstruction, guard);
+        obstructed.by_ref().for_each(drop);
+        obstructed.looped
+    }
 }
 
+/**
+ * Walks the guard one step at a time, yielding the position that was stepped from. Ends when the
+ * guard leaves the map, or when the guard reaches a tile with a heading it has already had there,
+ * in which case the walk is a loop
+ */
 impl Iterator for Map {
     type Item = (isize, isize);
 
     fn next(&mut self) -> Option<Self::Item> {
 
-        let flat_guard_pos = self.flat(self.guard.position)? as usize;
+        let position = self.guard.position;
+        let flat_guard_pos = self.flat(position)? as usize;
 
         if let TileState::Clear(ref mut directions) = self.tiles[flat_guard_pos]
         {
-            if directions.len() == 0
+            if directions.is_empty()
             {
                 self.visited += 1;
             }
-            directions.insert(self.guard.direction);
-
-            if let Some(forward_tile_flat_pos) = self.flat(self.guard.peek_forward())
+            if !directions.insert(self.guard.direction)
             {
-                if let TileState::Clear(_) = self.tiles[forward_tile_flat_pos as usize]
-                {
-                    if let Some(right_tile_flat_pos) = self.flat(self.guard.peek_right())
-                    {
-                        if let TileState::Clear(ref directions) = self.tiles[right_tile_flat_pos as usize]
-                        {
-                            if directions.contains(&self.guard.direction.right())
-                            {
-                                self.loop_insert_count += 1;
-                            }
-                        }
-                    }
-                }
+                self.looped = true;
+                return None;
             }
         }
 
         self.guard.next(&self.tiles[flat_guard_pos]);
-        Some((self.visited, 0))
+        Some(position)
     }
 }
 
@@ -216,23 +348,14 @@ impl fmt::Display for Map {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     
         let mut repr = String::new();
-        for (i, tile) in self.tiles.iter().enumerate()
+        for i in 0..self.tiles.len()
         {
             if i > 0 && i % self.width == 0
             {
                 repr.push('\n');
             }
 
-            if self.unflat(i) == self.guard.position
-            {
-                repr.push_str(self.guard.to_string().as_str());
-                continue;
-            }
-
-            repr.push(match tile {
-                TileState::Clear(directions) => if directions.len() > 0 { 'X' } else { '.' },
-                TileState::Obstacle => '#'
-            })
+            repr.push(self.tile_symbol(i));
         }
 
         write!(f, "{}", repr)
@@ -244,15 +367,109 @@ pub struct SolverDay06 {}
 
 impl Solver for SolverDay06
 {
-    fn solve_impl<'a>(lines: Vec<&'a str>) -> SolutionResult
+    fn solve_impl(lines: Vec<&str>) -> SolutionResult
+    {
+        let mut map = Map::new(lines)?;
+
+        // Any loop must be caused by an obstruction on the original path, so only the tiles the
+        // guard walks onto are tried, each from the guard state just before reaching it
+        let candidates = map.obstruction_candidates();
+
+        let loop_count = candidates.iter().filter(|(obstruction, guard)| map.loops(*obstruction, guard)).count();
+
+        Ok(Solution { part1: map.visited, part2: loop_count as isize })
+    }
+
+    // A 'width' by 'height' map where each tile is an obstacle with chance 'obstacles', and the guard starts on a clear one
+    fn generate(options: &GenerateOptions) -> anyhow::Result<String>
+    {
+        let width: usize = options.knob("width", 130)?;
+        let height: usize = options.knob("height", 130)?;
+        let obstacles = options.ratio("obstacles", 0.05)?;
+        if width == 0 || height == 0
+        {
+            anyhow::bail!("The map can't be empty");
+        }
+
+        let mut random = options.random();
+        let mut tiles: Vec<Vec<char>> = (0..height)
+            .map(|_| (0..width).map(|_| if random.chance(obstacles) { '#' } else { '.' }).collect())
+            .collect();
+
+        let (x, y) = (random.below(width), random.below(height));
+        tiles[y][x] = '^';
+
+        Ok(tiles.iter().map(|row| format!("{}\n", row.iter().collect::<String>())).collect())
+    }
+
+    fn inspect_impl(lines: Vec<&str>) -> anyhow::Result<String>
+    {
+        let map = Map::new(lines)?;
+        let obstacles = map.tiles.iter().filter(|tile| matches!(tile, TileState::Obstacle)).count();
+        Ok(format!("{}x{} map with {} obstacles, guard at {:?} facing {}\n{}",
+            map.width, map.height, obstacles, map.guard.position, map.guard.direction, map))
+    }
+
+    /**
+     * Replays the guard's walk a step at a time. Part 1 shows the original walk, part 2 shows the
+     * walk for each obstruction that causes a loop, ending on a frame with the loop highlighted
+     */
+    fn visualise_impl(lines: Vec<&str>, part: u8, sink: &mut dyn FrameSink) -> anyhow::Result<()>
     {
-        let mut result = Solution::default();
+        let mut map = Map::new(lines)?;
+
+        if part == 1
+        {
+            loop
+            {
+                if !map.guard_on_obstacle()
+                {
+                    let caption = format!("Part 1 | visited {}", map.visited);
+                    if sink.push(Frame::from_display(caption, &map))? != Flow::Continue
+                    {
+                        return Ok(());
+                    }
+                }
+                if map.next().is_none()
+                {
+                    break;
+                }
+            }
+            sink.push(Frame::from_display(format!("Part 1 | guard left after visiting {} tiles", map.visited), &map))?;
+            return Ok(());
+        }
 
-        let map = Map::new(lines);
+        let candidates = map.obstruction_candidates();
+        let looping: Vec<_> = candidates.iter().filter(|(obstruction, guard)| map.loops(*obstruction, guard)).collect();
+
+        for (count, (obstruction, guard)) in looping.iter().enumerate()
+        {
+            let caption = format!("Part 2 | obstruction {} of {} at {:?}", count + 1, looping.len(), obstruction);
+            let mut obstructed = map.with_obstruction(*obstruction, guard);
 
-        (result.part1, result.part2) = map.last().unwrap_or((0, 0));
+            let mut flow = Flow::Continue;
+            while flow == Flow::Continue && obstructed.next().is_some()
+            {
+                if !obstructed.guard_on_obstacle()
+                {
+                    flow = sink.push(obstructed.highlighted_frame(caption.clone(), &HashSet::new()))?;
+                }
+            }
 
-        Ok(result)
+            if flow == Flow::Continue
+            {
+                let loop_tiles = obstructed.loop_tiles();
+                let caption = format!("{} | loop of {} tiles", caption, loop_tiles.len());
+                flow = sink.push(obstructed.highlighted_frame(caption, &loop_tiles))?;
+            }
+
+            if flow == Flow::Quit
+            {
+                break;
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -279,6 +496,68 @@ mod test
     
     let solution = SolverDay06::solve(Box::new(sample.split('\n'))).unwrap();
     assert_eq!(solution.part1, 41);
-    // assert_eq!(solution.part2, 6);
+    assert_eq!(solution.part2, 6);
+    }
+
+    #[test]
+    fn test_generate()
+    {
+        let options = GenerateOptions::new(6).with_knobs("width=40,height=30,obstacles=0.1").unwrap();
+        let input = SolverDay06::generate(&options).unwrap();
+        assert_eq!(input.lines().count(), 30);
+        assert!(input.lines().all(|line| line.len() == 40));
+        assert_eq!(input.matches('^').count(), 1);
+        assert!(SolverDay06::solve(Box::new(input.lines())).is_ok());
+
+        // With nothing in the way the guard walks straight off the top
+        let options = GenerateOptions::new(6).with_knobs("width=40,height=30,obstacles=0").unwrap();
+        let input = SolverDay06::generate(&options).unwrap();
+        let row = input.lines().position(|line| line.contains('^')).unwrap();
+        let solution = SolverDay06::solve(Box::new(input.lines())).unwrap();
+        assert_eq!((solution.part1, solution.part2), (row as isize + 1, 0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_inspect()
+    {
+        let text = SolverDay06::inspect(Box::new(["..#", "#^.", "..."].into_iter())).unwrap();
+        assert_eq!(text, "3x3 map with 2 obstacles, guard at (1, 1) facing ^\n..#\n#^.\n...");
+    }
+
+    #[test]
+    fn test_invalid_maps()
+    {
+        let error = |lines: &[&str]| Map::new(lines.to_vec()).err().unwrap().to_string();
+
+        assert_eq!(error(&["..#", "^.", "..."]), "Row 2 is 2 wide, but the first row is 3");
+        assert_eq!(error(&["..#", "...", "..."]), "There is no guard '^' on the map");
+        assert_eq!(error(&["^.#", "...", "..^"]), "There is more than one guard, the second at (2, 2)");
+        assert_eq!(error(&["..#", ".^.", ".>."]), "Unexpected '>' at (1, 2), tiles are '.', '#' or '^'");
+        assert!(SolverDay06::solve(Box::new(std::iter::empty())).is_err());
+    }
+
+    #[test]
+    fn test_loop_tiles()
+    {
+        let sample = vec![
+            "....#.....",
+            ".........#",
+            "..........",
+            "..#.......",
+            ".......#..",
+            "..........",
+            ".#..^.....",
+            "........#.",
+            "#.........",
+            "......#...",
+        ];
+
+        let map = Map::new(sample).unwrap();
+        let mut obstructed = map.with_obstruction((3, 6), &map.guard);
+        obstructed.by_ref().for_each(drop);
+
+        assert!(obstructed.looped);
+        assert_eq!(obstructed.loop_tiles().len(), 18);
+        assert_eq!(obstructed.to_string().lines().nth(6), Some(".#.O^---+."));
+    }
+}
@@ -3,4 +3,16 @@ pub mod day02;
 pub mod day03;
 pub mod day04;
 pub mod day05;
-pub mod day06;
\ No newline at end of file
+pub mod day06;
+
+use super::Day;
+
+// Each day solved so far, and the solver for it
+pub const DAYS: &[Day] = &[
+    Day::of::<day01::SolverDay01>(1),
+    Day::of::<day02::SolverDay02>(2),
+    Day::of::<day03::SolverDay03>(3),
+    Day::of::<day04::SolverDay04>(4),
+    Day::of::<day05::SolverDay05>(5),
+    Day::of::<day06::SolverDay06>(6),
+];
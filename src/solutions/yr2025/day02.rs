@@ -0,0 +1,11 @@
+use crate::solver::{NotStarted, Solver, SolutionResult};
+
+pub struct SolverDay02 {}
+
+impl Solver for SolverDay02
+{
+    fn solve_impl(_lines: Vec<&str>) -> SolutionResult
+    {
+        Err(NotStarted.into())
+    }
+}
@@ -0,0 +1,11 @@
+use crate::solver::{NotStarted, Solver, SolutionResult};
+
+pub struct SolverDay04 {}
+
+impl Solver for SolverDay04
+{
+    fn solve_impl(_lines: Vec<&str>) -> SolutionResult
+    {
+        Err(NotStarted.into())
+    }
+}
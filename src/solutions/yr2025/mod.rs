@@ -0,0 +1,30 @@
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+
+use super::Day;
+
+// Each day, with those still only a stub marked as not started
+pub const DAYS: &[Day] = &[
+    Day::of::<day01::SolverDay01>(1).not_started(),
+    Day::of::<day02::SolverDay02>(2).not_started(),
+    Day::of::<day03::SolverDay03>(3).not_started(),
+    Day::of::<day04::SolverDay04>(4).not_started(),
+    Day::of::<day05::SolverDay05>(5).not_started(),
+    Day::of::<day06::SolverDay06>(6).not_started(),
+    Day::of::<day07::SolverDay07>(7).not_started(),
+    Day::of::<day08::SolverDay08>(8).not_started(),
+    Day::of::<day09::SolverDay09>(9).not_started(),
+    Day::of::<day10::SolverDay10>(10).not_started(),
+    Day::of::<day11::SolverDay11>(11).not_started(),
+    Day::of::<day12::SolverDay12>(12).not_started(),
+];
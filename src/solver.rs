@@ -1,5 +1,8 @@
 use std::fmt;
 
+use crate::generate::GenerateOptions;
+use crate::visualise::FrameSink;
+
 #[derive(Default, Debug)]
 pub struct Solution
 {
@@ -15,6 +18,47 @@ impl fmt::Display for Solution {
 
 pub type SolutionResult = anyhow::Result<Solution>;
 
+/**
+ * The error given by days that have been set up but not solved yet, so that they can be told
+ * apart from solvers that failed
+ */
+#[derive(Debug)]
+pub struct NotStarted;
+
+impl fmt::Display for NotStarted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not started")
+    }
+}
+
+impl std::error::Error for NotStarted {}
+
+// A part number given by the user. Every puzzle has two, and anything else is a mistake rather than part 2
+pub fn parse_part(text: &str) -> anyhow::Result<u8>
+{
+    match text.trim().parse::<u8>() {
+        Ok(part @ (1 | 2)) => Ok(part),
+        _ => anyhow::bail!("'{}' is not a part, which must be 1 or 2", text)
+    }
+}
+
+pub struct ExplainOptions
+{
+    pub part: u8,
+    // Leave out terminal colours, for output that can be compared with the puzzle text
+    pub plain: bool,
+    // Free-form text for solvers that can explain a search of their own, which each reads its own way
+    pub query: Option<String>
+}
+
+impl Default for ExplainOptions
+{
+    fn default() -> Self
+    {
+        Self { part: 1, plain: false, query: None }
+    }
+}
+
 pub trait Solver {
     fn clean<'a>(lines: Box<dyn Iterator<Item = &'a str> + 'a>) -> Vec<&'a str>
     {
@@ -26,12 +70,54 @@ pub trait Solver {
         }).collect()
     }
 
-    fn solve_impl<'a>(lines: Vec<&'a str>) -> SolutionResult;
+    fn solve_impl(lines: Vec<&str>) -> SolutionResult;
 
     fn solve<'a>(lines: Box<dyn Iterator<Item = &'a str> + 'a>) -> SolutionResult
     {
         Self::solve_impl(Self::clean(lines))
     }
+
+    /**
+     * Solvers that can show their intermediate state push frames to the sink as they go,
+     * stopping early if the sink asks them to
+     */
+    fn visualise_impl(_lines: Vec<&str>, _part: u8, _sink: &mut dyn FrameSink) -> anyhow::Result<()>
+    {
+        Err(anyhow::anyhow!("No visualisation implemented"))
+    }
+
+    fn visualise<'a>(lines: Box<dyn Iterator<Item = &'a str> + 'a>, part: u8, sink: &mut dyn FrameSink) -> anyhow::Result<()>
+    {
+        Self::visualise_impl(Self::clean(lines), part, sink)
+    }
+
+    // Solvers that can show how they reached an answer describe it here, for checking by hand
+    fn explain_impl(_lines: Vec<&str>, _options: &ExplainOptions) -> anyhow::Result<String>
+    {
+        Err(anyhow::anyhow!("No explanation implemented"))
+    }
+
+    fn explain<'a>(lines: Box<dyn Iterator<Item = &'a str> + 'a>, options: &ExplainOptions) -> anyhow::Result<String>
+    {
+        Self::explain_impl(Self::clean(lines), options)
+    }
+
+    // The input as the solver has parsed it, for looking over while working on the puzzle
+    fn inspect_impl(_lines: Vec<&str>) -> anyhow::Result<String>
+    {
+        Err(anyhow::anyhow!("No inspection implemented"))
+    }
+
+    fn inspect<'a>(lines: Box<dyn Iterator<Item = &'a str> + 'a>) -> anyhow::Result<String>
+    {
+        Self::inspect_impl(Self::clean(lines))
+    }
+
+    // A random input in the puzzle's format, from the seed and size knobs in the options
+    fn generate(_options: &GenerateOptions) -> anyhow::Result<String>
+    {
+        Err(anyhow::anyhow!("No generator implemented"))
+    }
 }
 
 
@@ -45,7 +131,7 @@ mod test
 
     impl Solver for TestSolver
     {
-        fn solve_impl<'a>(_lines: Vec<&'a str>) -> SolutionResult
+        fn solve_impl(_lines: Vec<&str>) -> SolutionResult
         {
             Ok(Solution::default())
         }
@@ -67,5 +153,15 @@ mod test
 
         assert_eq!(TestSolver::clean(Box::new(input.into_iter())), expected);
     }
-}
 
+    #[test]
+    fn test_parse_part()
+    {
+        assert_eq!(parse_part("1").unwrap(), 1);
+        assert_eq!(parse_part("2").unwrap(), 2);
+        for text in ["0", "3", "255", "two"]
+        {
+            assert_eq!(parse_part(text).unwrap_err().to_string(), format!("'{}' is not a part, which must be 1 or 2", text));
+        }
+    }
+}
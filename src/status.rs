@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::runner::{input_path, read_input};
+use crate::solutions;
+use crate::solver::NotStarted;
+
+const DAYS_PER_ROW: usize = 5;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PartStatus
+{
+    // Solved, but there's no input to run it on
+    NoInput,
+    NotStarted,
+    Failed,
+    // Answered, but there's no known answer to check against
+    Unverified,
+    Wrong,
+    Correct
+}
+
+impl PartStatus
+{
+    fn symbol(&self) -> char
+    {
+        match self {
+            PartStatus::NoInput => '+',
+            PartStatus::NotStarted => '.',
+            PartStatus::Failed => '!',
+            PartStatus::Unverified => '?',
+            PartStatus::Wrong => 'x',
+            PartStatus::Correct => '*'
+        }
+    }
+
+    // As given by the server's /verify
+    pub(crate) fn name(&self) -> &'static str
+    {
+        match self {
+            PartStatus::NoInput => "no input",
+            PartStatus::NotStarted => "not started",
+            PartStatus::Failed => "failed",
+            PartStatus::Unverified => "unverified",
+            PartStatus::Wrong => "wrong",
+            PartStatus::Correct => "correct"
+        }
+    }
+
+    fn check(answer: isize, expected: Option<&isize>) -> Self
+    {
+        match expected {
+            None => PartStatus::Unverified,
+            Some(expected) if *expected == answer => PartStatus::Correct,
+            Some(_) => PartStatus::Wrong
+        }
+    }
+}
+
+pub(crate) struct DayStatus
+{
+    pub(crate) day: u8,
+    pub(crate) input: bool,
+    pub(crate) parts: [PartStatus; 2],
+    pub(crate) time: Option<Duration>
+}
+
+fn format_time(time: Option<Duration>) -> String
+{
+    match time {
+        None => String::new(),
+        Some(time) if time < Duration::from_millis(1) => "<1ms".to_string(),
+        Some(time) if time < Duration::from_secs(1) => format!("{}ms", time.as_millis()),
+        Some(time) => format!("{:.1}s", time.as_secs_f64())
+    }
+}
+
+// Day number, whether there is input, each part's symbol and how long solving took
+impl fmt::Display for DayStatus
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let input = if self.input { 'i' } else { '-' };
+        let time = format_time(self.time);
+        write!(f, "{:02} {}{}{} {:>6}", self.day, input, self.parts[0].symbol(), self.parts[1].symbol(), time)
+    }
+}
+
+fn answers_path(year: &str) -> PathBuf
+{
+    PathBuf::from(format!("./input/{year}/answers.txt"))
+}
+
+/**
+ * Known answers for a year, one day per line as 'dayNN part1 part2'. Either answer may be left
+ * off, and a missing file means no answers are known yet
+ */
+fn parse_answers(text: &str) -> anyhow::Result<HashMap<u8, Vec<isize>>>
+{
+    let mut answers = HashMap::new();
+
+    for (line_number, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty())
+    {
+        let mut fields = line.split_whitespace();
+        let day = fields.next()
+            .and_then(|day| day.strip_prefix("day"))
+            .and_then(|day| day.parse::<u8>().ok())
+            .ok_or_else(|| anyhow::anyhow!("Line {} of the answers should start with 'dayNN'", line_number + 1))?;
+        let parts = fields.map(|answer| answer.parse::<isize>()).collect::<Result<Vec<isize>, _>>()?;
+        answers.insert(day, parts);
+    }
+
+    Ok(answers)
+}
+
+fn load_answers(year: &str) -> anyhow::Result<HashMap<u8, Vec<isize>>>
+{
+    match fs::read_to_string(answers_path(year)) {
+        Ok(text) => parse_answers(&text),
+        Err(_) => Ok(HashMap::new())
+    }
+}
+
+fn day_status(year: &str, day: u8, expected: Option<&Vec<isize>>) -> DayStatus
+{
+    // Without input, days are still run on no lines at all to see whether they've been started
+    let input = input_path(year, day).exists();
+    let lines = if input { read_input(year, day) } else { Vec::new() };
+    let start = Instant::now();
+    let result = solutions::solve(year, day, Box::new(lines.iter().map(|s| s.as_str())));
+    let time = start.elapsed();
+
+    let expected = |part: usize| expected.and_then(|answers| answers.get(part));
+    match result {
+        Err(e) if e.is::<NotStarted>() => DayStatus { day, input, parts: [PartStatus::NotStarted; 2], time: None },
+        _ if !input => DayStatus { day, input, parts: [PartStatus::NoInput; 2], time: None },
+        Ok(solution) => DayStatus {
+            day,
+            input: true,
+            parts: [PartStatus::check(solution.part1, expected(0)), PartStatus::check(solution.part2, expected(1))],
+            time: Some(time)
+        },
+        Err(_) => DayStatus { day, input: true, parts: [PartStatus::Failed; 2], time: Some(time) }
+    }
+}
+
+// Solves each day of a year that was compiled in, checking the answers against the known ones
+pub(crate) fn verify_year(year: &str, days: u8) -> anyhow::Result<Vec<DayStatus>>
+{
+    let answers = load_answers(year)?;
+    Ok((1..=days).map(|day| day_status(year, day, answers.get(&day))).collect())
+}
+
+pub const LEGEND: &str = "* correct   x wrong   ? no answer to check   + no input   ! failed   . not started   i input present";
+
+// One year's days as rows of the calendar, with its stars underneath
+pub fn year_calendar(year: &str, days: u8) -> anyhow::Result<String>
+{
+    let statuses = verify_year(year, days)?;
+
+    let mut calendar = format!("{}\n", year);
+    for row in statuses.chunks(DAYS_PER_ROW)
+    {
+        let cells: Vec<String> = row.iter().map(|status| status.to_string()).collect();
+        calendar.push_str(&format!("  {}\n", cells.join("   ").trim_end()));
+    }
+
+    let stars = statuses.iter().flat_map(|status| status.parts).filter(|part| *part == PartStatus::Correct).count();
+    calendar.push_str(&format!("  {}/{} stars\n", stars, 2 * days as usize));
+    Ok(calendar)
+}
+
+/**
+ * Solves every day of every year, and lays the results out as a calendar. Each day shows 'i'
+ * if its input is there, a symbol for each part, and how long solving took
+ */
+pub fn status() -> anyhow::Result<String>
+{
+    let mut status = String::new();
+    for year in solutions::YEARS
+    {
+        if !solutions::compiled_in(year)
+        {
+            status.push_str(&format!("{}\n  not compiled in\n\n", year));
+            continue;
+        }
+        status.push_str(&year_calendar(year, solutions::days(year).unwrap())?);
+        status.push('\n');
+    }
+    status.push_str(LEGEND);
+    Ok(status)
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn test_parse_answers()
+    {
+        let answers = parse_answers("day01 11 31\n\nday02 2\n").unwrap();
+        assert_eq!(answers[&1], vec![11, 31]);
+        assert_eq!(answers[&2], vec![2]);
+
+        assert!(parse_answers("one 11 31").is_err());
+        assert!(parse_answers("day01 eleven").is_err());
+    }
+
+    #[cfg(all(feature = "y2024", feature = "y2025"))]
+    #[test]
+    fn test_day_status()
+    {
+        let expected = vec![1830467, 1];
+        let status = day_status("2024", 1, Some(&expected));
+        assert_eq!(status.parts, [PartStatus::Correct, PartStatus::Wrong]);
+        assert!(status.to_string().starts_with("01 i*x"));
+
+        assert_eq!(day_status("2024", 1, None).parts, [PartStatus::Unverified; 2]);
+        assert_eq!(day_status("2024", 25, None).parts, [PartStatus::NotStarted; 2]);
+        assert!(!day_status("2025", 1, None).input);
+    }
+
+    #[test]
+    fn test_format_time()
+    {
+        assert_eq!(format_time(Some(Duration::from_micros(10))), "<1ms");
+        assert_eq!(format_time(Some(Duration::from_millis(12))), "12ms");
+        assert_eq!(format_time(Some(Duration::from_millis(8140))), "8.1s");
+    }
+}
@@ -1 +1,4 @@
-pub mod yr2024;
\ No newline at end of file
+#[cfg(feature = "y2024")]
+pub mod yr2024;
+#[cfg(feature = "y2025")]
+pub mod yr2025;
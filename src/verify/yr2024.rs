@@ -2,7 +2,6 @@
 use crate::runner::get_solution;
 use crate::solver::Solution;
 
-use super::*;
 use matches::assert_matches;
 
 #[test]
@@ -38,5 +37,5 @@ fn day05()
 #[test]
 fn day06()
 {
-    assert_matches!(get_solution("2024", 6), Ok(Solution { part1: 5312, part2: 0 }));
+    assert_matches!(get_solution("2024", 6), Ok(Solution { part1: 5312, part2: 1748 }));
 }
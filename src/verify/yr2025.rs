@@ -1,6 +1,15 @@
-
 use crate::runner::get_solution;
-use crate::solver::Solution;
+use crate::solutions;
+use crate::solver::NotStarted;
 
-use super::*;
-use matches::assert_matches;
\ No newline at end of file
+// Each day is registered, and says it hasn't been started rather than failing. Days move out of
+// here into tests of their own as they are solved, as in yr2024
+#[test]
+fn not_started()
+{
+    for day in 1..=solutions::days("2025").unwrap()
+    {
+        let result = get_solution("2025", day);
+        assert!(result.as_ref().is_err_and(|e| e.is::<NotStarted>()), "Day {:02}: {:?}", day, result);
+    }
+}
@@ -0,0 +1,440 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::{Cell, Colour, Content, Flow, Frame, FrameSink, Grid};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb
+{
+    // Parses 'rrggbb', with or without a leading '#'
+    pub fn parse(hex: &str) -> anyhow::Result<Self>
+    {
+        let hex = hex.trim_start_matches('#');
+        // Checked byte by byte before slicing, which non-ASCII characters would split
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            anyhow::bail!("Colour '{}' should be six hex digits", hex);
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    fn hex(&self) -> String
+    {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/**
+ * Maps grid cells to the colour they are drawn in. A cell's highlight colour takes priority,
+ * then its symbol, falling back on the default colour
+ */
+#[derive(Clone, Debug)]
+pub struct Palette
+{
+    background: Rgb,
+    default: Rgb,
+    symbols: HashMap<char, Rgb>,
+    colours: HashMap<Colour, Rgb>
+}
+
+impl Default for Palette
+{
+    fn default() -> Self
+    {
+        let background = Rgb(0x00, 0x2b, 0x36);
+        Self {
+            background,
+            default: Rgb(0x93, 0xa1, 0xa1),
+            symbols: HashMap::from([('.', background), (' ', background), ('#', Rgb(0xee, 0xe8, 0xd5))]),
+            colours: HashMap::from([
+                (Colour::Red, Rgb(0xdc, 0x32, 0x2f)),
+                (Colour::Green, Rgb(0x85, 0x99, 0x00)),
+                (Colour::Yellow, Rgb(0xb5, 0x89, 0x00)),
+                (Colour::Blue, Rgb(0x26, 0x8b, 0xd2)),
+                (Colour::Magenta, Rgb(0xd3, 0x36, 0x82)),
+                (Colour::Cyan, Rgb(0x2a, 0xa1, 0x98)),
+                (Colour::White, Rgb(0xff, 0xff, 0xff)),
+            ])
+        }
+    }
+}
+
+impl Palette
+{
+    /**
+     * Applies comma separated 'key=rrggbb' overrides. A single character key maps that symbol,
+     * otherwise the key is 'background', 'default' or the name of a highlight colour
+     */
+    pub fn with_overrides(mut self, spec: &str) -> anyhow::Result<Self>
+    {
+        for entry in spec.split(',').filter(|entry| !entry.is_empty())
+        {
+            let (key, value) = entry.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Palette entry '{}' should be 'key=rrggbb'", entry))?;
+            let rgb = Rgb::parse(value)?;
+
+            let mut key_chars = key.chars();
+            match (key_chars.next(), key_chars.next()) {
+                (Some(symbol), None) => { self.symbols.insert(symbol, rgb); },
+                _ => match key.to_lowercase().as_str() {
+                    "background" => self.background = rgb,
+                    "default" => self.default = rgb,
+                    name => match Colour::from_name(name) {
+                        Some(colour) => { self.colours.insert(colour, rgb); },
+                        None => anyhow::bail!("Unknown palette key '{}'", key)
+                    }
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn cell_colour(&self, cell: &Cell) -> Rgb
+    {
+        cell.colour.and_then(|colour| self.colours.get(&colour))
+            .or_else(|| self.symbols.get(&cell.symbol))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    // Every colour the palette can produce, background first
+    fn table(&self) -> Vec<Rgb>
+    {
+        let mut table = vec![self.background, self.default];
+        for rgb in self.symbols.values().chain(self.colours.values())
+        {
+            if !table.contains(rgb)
+            {
+                table.push(*rgb);
+            }
+        }
+        table
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ImageFormat
+{
+    Svg,
+    Png,
+    Gif
+}
+
+impl ImageFormat
+{
+    fn from_path(path: &Path) -> anyhow::Result<Self>
+    {
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase()).as_deref() {
+            Some("svg") => Ok(ImageFormat::Svg),
+            Some("png") => Ok(ImageFormat::Png),
+            Some("gif") => Ok(ImageFormat::Gif),
+            _ => Err(anyhow::anyhow!("Can't tell the image format of '{}', use .svg, .png or .gif", path.display()))
+        }
+    }
+}
+
+pub struct ExportOptions
+{
+    pub path: Option<PathBuf>,
+    pub cell_size: u32,
+    pub palette: Palette,
+    // SVGs draw each cell's symbol in its colour, rather than filling the cell
+    pub symbols: bool,
+    // Animations keep every nth frame, plus the last
+    pub every: usize
+}
+
+impl Default for ExportOptions
+{
+    fn default() -> Self
+    {
+        Self { path: None, cell_size: 8, palette: Palette::default(), symbols: false, every: 1 }
+    }
+}
+
+// One colour per pixel, with short rows padded by the background
+fn rasterise(grid: &Grid, palette: &Palette, cell_size: u32, width: usize, height: usize) -> Vec<Rgb>
+{
+    let cell_size = cell_size as usize;
+    let pixel_width = width * cell_size;
+    let mut pixels = vec![palette.background; pixel_width * height * cell_size];
+
+    for (y, row) in grid.rows().iter().enumerate().take(height)
+    {
+        for (x, cell) in row.iter().enumerate().take(width)
+        {
+            let rgb = palette.cell_colour(cell);
+            for dy in 0..cell_size
+            {
+                let start = (y * cell_size + dy) * pixel_width + x * cell_size;
+                pixels[start..start + cell_size].fill(rgb);
+            }
+        }
+    }
+    pixels
+}
+
+pub fn svg(grid: &Grid, palette: &Palette, cell_size: u32, symbols: bool) -> String
+{
+    let (width, height) = (grid.width() as u32 * cell_size, grid.height() as u32 * cell_size);
+
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n");
+    svg.push_str(&format!("<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n", palette.background.hex()));
+    if symbols
+    {
+        svg.push_str(&format!("<g font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">\n", cell_size));
+    }
+
+    for (y, row) in grid.rows().iter().enumerate()
+    {
+        for (x, cell) in row.iter().enumerate()
+        {
+            let rgb = palette.cell_colour(cell);
+            let (px, py) = (x as u32 * cell_size, y as u32 * cell_size);
+
+            if symbols && !cell.symbol.is_whitespace()
+            {
+                let symbol = match cell.symbol {
+                    '<' => "&lt;".to_string(),
+                    '>' => "&gt;".to_string(),
+                    '&' => "&amp;".to_string(),
+                    c => c.to_string()
+                };
+                svg.push_str(&format!("<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>\n", px + cell_size / 2, py + cell_size / 2, rgb.hex(), symbol));
+            }
+            else if !symbols && rgb != palette.background
+            {
+                svg.push_str(&format!("<rect x=\"{px}\" y=\"{py}\" width=\"{cell_size}\" height=\"{cell_size}\" fill=\"{}\"/>\n", rgb.hex()));
+            }
+        }
+    }
+
+    if symbols
+    {
+        svg.push_str("</g>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub fn write_png(grid: &Grid, palette: &Palette, cell_size: u32, path: &Path) -> anyhow::Result<()>
+{
+    let pixels = rasterise(grid, palette, cell_size, grid.width(), grid.height());
+    let data: Vec<u8> = pixels.iter().flat_map(|rgb| [rgb.0, rgb.1, rgb.2]).collect();
+
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), grid.width() as u32 * cell_size, grid.height() as u32 * cell_size);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&data)?;
+    Ok(())
+}
+
+/**
+ * Writes frames into an animated GIF as they are pushed. The image is sized by the first frame,
+ * and uses the palette as its colour table so frames don't need to be quantised
+ */
+struct GifWriter
+{
+    encoder: gif::Encoder<BufWriter<File>>,
+    palette: Palette,
+    indices: HashMap<Rgb, u8>,
+    cell_size: u32,
+    width: usize,
+    height: usize,
+    delay: u16
+}
+
+impl GifWriter
+{
+    fn create(path: &Path, first: &Grid, palette: &Palette, cell_size: u32, delay: Duration) -> anyhow::Result<Self>
+    {
+        let table = palette.table();
+        if table.len() > 256
+        {
+            anyhow::bail!("GIFs are limited to 256 colours, the palette has {}", table.len());
+        }
+        let flat_table: Vec<u8> = table.iter().flat_map(|rgb| [rgb.0, rgb.1, rgb.2]).collect();
+
+        let (width, height) = (first.width(), first.height());
+        let pixel_size = |cells: usize| u16::try_from(cells * cell_size as usize)
+            .map_err(|_| anyhow::anyhow!("Grid is too large for a GIF at {} pixels per cell", cell_size));
+
+        let mut encoder = gif::Encoder::new(BufWriter::new(File::create(path)?), pixel_size(width)?, pixel_size(height)?, &flat_table)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        Ok(Self {
+            encoder,
+            palette: palette.clone(),
+            indices: table.iter().enumerate().map(|(idx, rgb)| (*rgb, idx as u8)).collect(),
+            cell_size,
+            width,
+            height,
+            // GIF delays are in hundredths of a second, and most viewers ignore anything below 2
+            delay: (delay.as_millis() / 10).clamp(2, u16::MAX as u128) as u16
+        })
+    }
+
+    fn write(&mut self, grid: &Grid) -> anyhow::Result<()>
+    {
+        let pixels = rasterise(grid, &self.palette, self.cell_size, self.width, self.height);
+        let buffer: Vec<u8> = pixels.iter().map(|rgb| self.indices[rgb]).collect();
+
+        let frame = gif::Frame {
+            width: (self.width * self.cell_size as usize) as u16,
+            height: (self.height * self.cell_size as usize) as u16,
+            buffer: Cow::Owned(buffer),
+            delay: self.delay,
+            ..gif::Frame::default()
+        };
+        self.encoder.write_frame(&frame)?;
+        Ok(())
+    }
+}
+
+/**
+ * Exports grid frames as images. SVGs and PNGs are a picture of the last grid frame, GIFs are
+ * an animation of all of them. Text frames are ignored
+ */
+pub struct Exporter
+{
+    path: PathBuf,
+    format: ImageFormat,
+    cell_size: u32,
+    palette: Palette,
+    symbols: bool,
+    every: usize,
+    delay: Duration,
+    gif: Option<GifWriter>,
+    pending: Option<Grid>,
+    frame_count: usize
+}
+
+impl Exporter
+{
+    pub fn new(path: &Path, options: &ExportOptions, delay: Duration) -> anyhow::Result<Self>
+    {
+        Ok(Self {
+            path: path.to_path_buf(),
+            format: ImageFormat::from_path(path)?,
+            cell_size: options.cell_size.max(1),
+            palette: options.palette.clone(),
+            symbols: options.symbols,
+            every: options.every.max(1),
+            delay,
+            gif: None,
+            pending: None,
+            frame_count: 0
+        })
+    }
+
+    fn write_gif_frame(&mut self, grid: &Grid) -> anyhow::Result<()>
+    {
+        if self.gif.is_none()
+        {
+            self.gif = Some(GifWriter::create(&self.path, grid, &self.palette, self.cell_size, self.delay)?);
+        }
+        self.gif.as_mut().map_or(Ok(()), |gif| gif.write(grid))
+    }
+}
+
+impl FrameSink for Exporter
+{
+    fn push(&mut self, frame: Frame) -> anyhow::Result<Flow>
+    {
+        let Content::Grid(grid) = frame.content else {
+            return Ok(Flow::Continue);
+        };
+
+        if self.format == ImageFormat::Gif && self.frame_count.is_multiple_of(self.every)
+        {
+            self.write_gif_frame(&grid)?;
+            self.pending = None;
+        }
+        else
+        {
+            self.pending = Some(grid);
+        }
+        self.frame_count += 1;
+
+        Ok(Flow::Continue)
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()>
+    {
+        let Some(grid) = self.pending.take() else {
+            if self.frame_count == 0
+            {
+                anyhow::bail!("No grid frames to export");
+            }
+            return Ok(());
+        };
+
+        match self.format {
+            ImageFormat::Svg => fs::write(&self.path, svg(&grid, &self.palette, self.cell_size, self.symbols))?,
+            ImageFormat::Png => write_png(&grid, &self.palette, self.cell_size, &self.path)?,
+            ImageFormat::Gif => self.write_gif_frame(&grid)?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn test_palette()
+    {
+        let palette = Palette::default().with_overrides("X=ff0000,background=000000,green=00ff00").unwrap();
+
+        assert_eq!(palette.cell_colour(&Cell::plain('X')), Rgb(0xff, 0, 0));
+        assert_eq!(palette.cell_colour(&Cell { symbol: 'X', colour: Some(Colour::Green) }), Rgb(0, 0xff, 0));
+        assert_eq!(palette.cell_colour(&Cell::plain('?')), palette.default);
+        assert_eq!(palette.table()[0], Rgb(0, 0, 0));
+
+        assert!(Palette::default().with_overrides("X=red").is_err());
+        assert!(Palette::default().with_overrides("X=aééa").is_err());
+        assert!(Palette::default().with_overrides("X=+f+f+f").is_err());
+        assert!(Palette::default().with_overrides("purple=ffffff").is_err());
+    }
+
+    #[test]
+    fn test_rasterise()
+    {
+        let palette = Palette::default().with_overrides("A=ffffff").unwrap();
+        let grid = Grid::from_display(&"A.\nA");
+
+        let pixels = rasterise(&grid, &palette, 2, grid.width(), grid.height());
+        let white = Rgb(0xff, 0xff, 0xff);
+        let bg = palette.background;
+
+        assert_eq!(pixels, vec![
+            white, white, bg, bg,
+            white, white, bg, bg,
+            white, white, bg, bg,
+            white, white, bg, bg,
+        ]);
+    }
+
+    #[test]
+    fn test_svg()
+    {
+        let mut grid = Grid::from_display(&"#<");
+        grid.set_colour(1, 0, Colour::Red);
+
+        let blocks = svg(&grid, &Palette::default(), 10, false);
+        assert!(blocks.contains("<rect x=\"0\" y=\"0\" width=\"10\" height=\"10\" fill=\"#eee8d5\"/>"));
+        assert!(blocks.contains("<rect x=\"10\" y=\"0\" width=\"10\" height=\"10\" fill=\"#dc322f\"/>"));
+
+        let symbols = svg(&grid, &Palette::default(), 10, true);
+        assert!(symbols.contains("<text x=\"15\" y=\"5\" fill=\"#dc322f\">&lt;</text>"));
+    }
+}
@@ -0,0 +1,320 @@
+pub mod export;
+pub mod recording;
+pub mod terminal;
+
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use export::{ExportOptions, Exporter};
+use recording::Recorder;
+use terminal::Player;
+
+pub const RESET: &str = "\x1b[0m";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Colour
+{
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White
+}
+
+impl Colour
+{
+    fn ansi_code(&self) -> u8
+    {
+        match self {
+            Colour::Red => 31,
+            Colour::Green => 32,
+            Colour::Yellow => 33,
+            Colour::Blue => 34,
+            Colour::Magenta => 35,
+            Colour::Cyan => 36,
+            Colour::White => 37
+        }
+    }
+
+    fn from_ansi_code(code: u8) -> Option<Colour>
+    {
+        match code {
+            31 => Some(Colour::Red),
+            32 => Some(Colour::Green),
+            33 => Some(Colour::Yellow),
+            34 => Some(Colour::Blue),
+            35 => Some(Colour::Magenta),
+            36 => Some(Colour::Cyan),
+            37 => Some(Colour::White),
+            _ => None
+        }
+    }
+
+    // Lower case names, as used in palettes
+    pub fn from_name(name: &str) -> Option<Colour>
+    {
+        match name {
+            "red" => Some(Colour::Red),
+            "green" => Some(Colour::Green),
+            "yellow" => Some(Colour::Yellow),
+            "blue" => Some(Colour::Blue),
+            "magenta" => Some(Colour::Magenta),
+            "cyan" => Some(Colour::Cyan),
+            "white" => Some(Colour::White),
+            _ => None
+        }
+    }
+
+    // Bold foreground colour escape sequence
+    pub fn escape(&self) -> String
+    {
+        format!("\x1b[1;{}m", self.ansi_code())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cell
+{
+    pub symbol: char,
+    pub colour: Option<Colour>
+}
+
+impl Cell
+{
+    pub fn plain(symbol: char) -> Self
+    {
+        Self { symbol, colour: None }
+    }
+}
+
+/**
+ * A grid of characters, each with an optional colour. Rows may be of different lengths, as
+ * they are in any text that is converted into a grid
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Grid
+{
+    rows: Vec<Vec<Cell>>
+}
+
+impl Grid
+{
+    pub fn new(rows: Vec<Vec<Cell>>) -> Self
+    {
+        Self { rows }
+    }
+
+    // Each line of the value's Display output becomes a row of uncoloured cells
+    pub fn from_display(value: &dyn fmt::Display) -> Self
+    {
+        Self { rows: value.to_string().lines().map(|line| line.chars().map(Cell::plain).collect()).collect() }
+    }
+
+    pub fn rows(&self) -> &[Vec<Cell>]
+    {
+        &self.rows
+    }
+
+    pub fn height(&self) -> usize
+    {
+        self.rows.len()
+    }
+
+    pub fn width(&self) -> usize
+    {
+        self.rows.iter().map(|row| row.len()).max().unwrap_or(0)
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell>
+    {
+        self.rows.get_mut(y)?.get_mut(x)
+    }
+
+    // Positions outside the grid are ignored
+    pub fn set_colour(&mut self, x: usize, y: usize, colour: Colour)
+    {
+        if let Some(cell) = self.get_mut(x, y)
+        {
+            cell.colour = Some(colour);
+        }
+    }
+}
+
+// Coloured cells are wrapped in ANSI escapes, so the output is meant for a terminal
+impl fmt::Display for Grid
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        for (y, row) in self.rows.iter().enumerate()
+        {
+            if y > 0
+            {
+                writeln!(f)?;
+            }
+            for cell in row
+            {
+                match cell.colour {
+                    Some(colour) => write!(f, "{}{}{}", colour.escape(), cell.symbol, RESET)?,
+                    None => write!(f, "{}", cell.symbol)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Content
+{
+    Grid(Grid),
+    Text(String)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame
+{
+    pub caption: String,
+    pub content: Content
+}
+
+impl Frame
+{
+    pub fn grid(caption: impl Into<String>, grid: Grid) -> Self
+    {
+        Self { caption: caption.into(), content: Content::Grid(grid) }
+    }
+
+    pub fn text(caption: impl Into<String>, text: impl Into<String>) -> Self
+    {
+        Self { caption: caption.into(), content: Content::Text(text.into()) }
+    }
+
+    pub fn from_display(caption: impl Into<String>, value: &dyn fmt::Display) -> Self
+    {
+        Self::grid(caption, Grid::from_display(value))
+    }
+}
+
+impl fmt::Display for Content
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            Content::Grid(grid) => write!(f, "{}", grid),
+            Content::Text(text) => write!(f, "{}", text)
+        }
+    }
+}
+
+// What the solver should do after pushing a frame
+#[derive(PartialEq, Eq, Debug)]
+pub enum Flow
+{
+    Continue,
+    Skip,
+    Quit
+}
+
+/**
+ * Solvers push the frames of a visualisation here, and stop or skip ahead when asked to
+ */
+pub trait FrameSink
+{
+    fn push(&mut self, frame: Frame) -> anyhow::Result<Flow>;
+
+    // Called once the last frame has been pushed
+    fn finish(&mut self) -> anyhow::Result<()>
+    {
+        Ok(())
+    }
+}
+
+// Passes every frame on to each sink, following the first one that asks to stop or skip
+impl FrameSink for Vec<Box<dyn FrameSink>>
+{
+    fn push(&mut self, frame: Frame) -> anyhow::Result<Flow>
+    {
+        let mut flow = Flow::Continue;
+        for sink in self.iter_mut()
+        {
+            let sink_flow = sink.push(frame.clone())?;
+            if flow == Flow::Continue
+            {
+                flow = sink_flow;
+            }
+        }
+        Ok(flow)
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()>
+    {
+        self.iter_mut().try_for_each(|sink| sink.finish())
+    }
+}
+
+pub struct VisualiseOptions
+{
+    pub part: u8,
+    pub delay: Duration,
+    pub paused: bool,
+    pub headless: bool,
+    pub record: Option<PathBuf>,
+    pub export: ExportOptions
+}
+
+impl Default for VisualiseOptions
+{
+    fn default() -> Self
+    {
+        Self {
+            part: 1,
+            delay: Duration::from_millis(50),
+            paused: false,
+            headless: false,
+            record: None,
+            export: ExportOptions::default()
+        }
+    }
+}
+
+// Headless runs only record or export frames, so they need somewhere to send them
+pub fn create_sink(options: &VisualiseOptions) -> anyhow::Result<Box<dyn FrameSink>>
+{
+    let mut sinks: Vec<Box<dyn FrameSink>> = Vec::new();
+
+    if let Some(path) = &options.record
+    {
+        sinks.push(Box::new(Recorder::create(path)?));
+    }
+    if let Some(path) = &options.export.path
+    {
+        sinks.push(Box::new(Exporter::new(path, &options.export, options.delay)?));
+    }
+    if !options.headless
+    {
+        sinks.push(Box::new(Player::new(options)));
+    }
+
+    match sinks.len() {
+        0 => Err(anyhow::anyhow!("Headless visualisation needs a file to record or export to")),
+        1 => Ok(sinks.remove(0)),
+        _ => Ok(Box::new(sinks))
+    }
+}
+
+// Plays back a file written by a Recorder, to the terminal or any other sink
+pub fn playback(path: &PathBuf, options: &VisualiseOptions) -> anyhow::Result<()>
+{
+    let mut sink = create_sink(options)?;
+    for frame in recording::read(path)?
+    {
+        if sink.push(frame)? == Flow::Quit
+        {
+            break;
+        }
+    }
+    sink.finish()
+}
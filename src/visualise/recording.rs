@@ -0,0 +1,177 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use super::{Cell, Colour, Content, Flow, Frame, FrameSink, Grid};
+
+const GRID_HEADER: &str = "--- grid | ";
+const TEXT_HEADER: &str = "--- text | ";
+
+/**
+ * Writes frames to a file as they are pushed, without showing them. Each frame is a header line
+ * holding the caption, then the content as it would be drawn in the terminal, so grid colours
+ * are kept as ANSI escapes. Content lines that could be mistaken for a header are escaped with
+ * a leading backslash
+ */
+pub struct Recorder
+{
+    writer: BufWriter<File>
+}
+
+impl Recorder
+{
+    pub fn create(path: &PathBuf) -> anyhow::Result<Self>
+    {
+        Ok(Self { writer: BufWriter::new(File::create(path)?) })
+    }
+}
+
+impl FrameSink for Recorder
+{
+    fn push(&mut self, frame: Frame) -> anyhow::Result<Flow>
+    {
+        let header = match frame.content {
+            Content::Grid(_) => GRID_HEADER,
+            Content::Text(_) => TEXT_HEADER
+        };
+        writeln!(self.writer, "{}{}", header, frame.caption)?;
+
+        for line in frame.content.to_string().lines()
+        {
+            if line.starts_with("---") || line.starts_with('\\')
+            {
+                write!(self.writer, "\\")?;
+            }
+            writeln!(self.writer, "{}", line)?;
+        }
+
+        Ok(Flow::Continue)
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()>
+    {
+        Ok(self.writer.flush()?)
+    }
+}
+
+// Converts a line drawn by Grid's Display back into cells
+fn decode_row(line: &str) -> Vec<Cell>
+{
+    let mut cells = Vec::new();
+    let mut colour = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next()
+    {
+        if c != '\x1b'
+        {
+            cells.push(Cell { symbol: c, colour });
+            continue;
+        }
+
+        let sequence: String = chars.by_ref().take_while(|c| *c != 'm').collect();
+        colour = sequence.trim_start_matches('[')
+            .split(';')
+            .filter_map(|code| code.parse::<u8>().ok())
+            .rev()
+            .find_map(Colour::from_ansi_code);
+    }
+
+    cells
+}
+
+fn finish_frame(frames: &mut Vec<Frame>, header: Option<(bool, String)>, lines: &mut Vec<&str>)
+{
+    if let Some((is_grid, caption)) = header
+    {
+        let frame = if is_grid
+        {
+            Frame::grid(caption, Grid::new(lines.iter().map(|line| decode_row(line)).collect()))
+        }
+        else
+        {
+            Frame::text(caption, lines.join("\n"))
+        };
+        frames.push(frame);
+    }
+    lines.clear();
+}
+
+pub fn parse(recording: &str) -> anyhow::Result<Vec<Frame>>
+{
+    let mut frames = Vec::new();
+    let mut header: Option<(bool, String)> = None;
+    let mut lines = Vec::new();
+
+    for (line_number, line) in recording.lines().enumerate()
+    {
+        let next_header = if let Some(caption) = line.strip_prefix(GRID_HEADER)
+        {
+            Some((true, caption.to_string()))
+        }
+        else
+        {
+            line.strip_prefix(TEXT_HEADER).map(|caption| (false, caption.to_string()))
+        };
+
+        if next_header.is_some()
+        {
+            finish_frame(&mut frames, header, &mut lines);
+            header = next_header;
+        }
+        else if header.is_none()
+        {
+            anyhow::bail!("Line {} comes before any frame header", line_number + 1);
+        }
+        else
+        {
+            lines.push(line.strip_prefix('\\').unwrap_or(line));
+        }
+    }
+    finish_frame(&mut frames, header, &mut lines);
+
+    Ok(frames)
+}
+
+pub fn read(path: &PathBuf) -> anyhow::Result<Vec<Frame>>
+{
+    parse(&fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn test_round_trip()
+    {
+        let mut grid = Grid::from_display(&"#..\n.X.\n---");
+        grid.set_colour(1, 1, Colour::Yellow);
+        grid.set_colour(0, 0, Colour::Red);
+
+        let frames = vec![
+            Frame::grid("first", grid),
+            Frame::text("second", "some text\n\\with a backslash"),
+        ];
+
+        let path = std::env::temp_dir().join(format!("aoc_recording_{}.txt", std::process::id()));
+        let mut recorder = Recorder::create(&path).unwrap();
+        for frame in frames.iter()
+        {
+            assert_eq!(recorder.push(frame.clone()).unwrap(), Flow::Continue);
+        }
+        drop(recorder);
+
+        let read_back = read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(read_back, frames);
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_header()
+    {
+        assert!(parse("...\n--- grid | late").is_err());
+    }
+}
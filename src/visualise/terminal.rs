@@ -0,0 +1,143 @@
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use super::{Flow, Frame, FrameSink, VisualiseOptions};
+
+// Redraws over the previous frame rather than clearing first, which avoids flicker
+const REDRAW: &str = "\x1b[H";
+const CLEAR_BELOW: &str = "\x1b[J";
+const CLEAR_LINE_END: &str = "\x1b[K";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const CLEAR_SCREEN: &str = "\x1b[2J";
+
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+/**
+ * Commands are read a line at a time from stdin, so each one is followed by Enter.
+ * An empty line steps a single frame
+ */
+enum Command
+{
+    TogglePause,
+    Step,
+    Faster,
+    Slower,
+    Skip,
+    Quit
+}
+
+impl Command
+{
+    fn parse(line: &str) -> Option<Command>
+    {
+        match line.trim() {
+            "" | "s" => Some(Command::Step),
+            "p" => Some(Command::TogglePause),
+            "+" => Some(Command::Faster),
+            "-" => Some(Command::Slower),
+            "n" => Some(Command::Skip),
+            "q" => Some(Command::Quit),
+            _ => None
+        }
+    }
+}
+
+/**
+ * Draws frames in the terminal at a fixed rate, with pause and step controls
+ */
+pub struct Player
+{
+    delay: Duration,
+    paused: bool,
+    commands: Receiver<Command>,
+    frame_count: usize
+}
+
+impl Player
+{
+    pub fn new(options: &VisualiseOptions) -> Self
+    {
+        let (sender, commands) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines().map_while(Result::ok)
+            {
+                if let Some(command) = Command::parse(&line)
+                {
+                    if sender.send(command).is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        });
+
+        print!("{}{}", CLEAR_SCREEN, HIDE_CURSOR);
+
+        Self { delay: options.delay, paused: options.paused, commands, frame_count: 0 }
+    }
+
+    // Blocks until the next frame is due, handling any commands that arrive in the meantime
+    fn wait(&mut self) -> Flow
+    {
+        loop
+        {
+            let command = if self.paused
+            {
+                match self.commands.recv() {
+                    Ok(command) => command,
+                    // Nothing left to read commands from, so play through to the end
+                    Err(_) => { self.paused = false; return Flow::Continue; }
+                }
+            }
+            else
+            {
+                match self.commands.recv_timeout(self.delay) {
+                    Ok(command) => command,
+                    Err(RecvTimeoutError::Timeout) => return Flow::Continue,
+                    Err(RecvTimeoutError::Disconnected) => { thread::sleep(self.delay); return Flow::Continue; }
+                }
+            };
+
+            match command {
+                Command::TogglePause => self.paused = !self.paused,
+                Command::Step => { self.paused = true; return Flow::Continue; },
+                Command::Faster => self.delay = (self.delay / 2).max(MIN_DELAY),
+                Command::Slower => self.delay = (self.delay * 2).min(MAX_DELAY),
+                Command::Skip => return Flow::Skip,
+                Command::Quit => return Flow::Quit
+            }
+        }
+    }
+}
+
+impl FrameSink for Player
+{
+    fn push(&mut self, frame: Frame) -> anyhow::Result<Flow>
+    {
+        self.frame_count += 1;
+
+        let mut stdout = io::stdout().lock();
+        writeln!(stdout, "{}{}{}", REDRAW, frame.caption, CLEAR_LINE_END)?;
+        for line in frame.content.to_string().lines()
+        {
+            writeln!(stdout, "{}{}", line, CLEAR_LINE_END)?;
+        }
+        write!(stdout, "frame {} | {} | {}ms | Enter: step, p: play/pause, +/-: speed, n: next, q: quit{}",
+            self.frame_count, if self.paused { "paused" } else { "playing" }, self.delay.as_millis(), CLEAR_BELOW)?;
+        stdout.flush()?;
+
+        Ok(self.wait())
+    }
+}
+
+impl Drop for Player
+{
+    fn drop(&mut self)
+    {
+        println!("{}", SHOW_CURSOR);
+    }
+}
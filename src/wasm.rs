@@ -0,0 +1,40 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::json::{json_error, json_object};
+use crate::solutions;
+
+/**
+ * Solves a day from input pasted into the page. Gives '{"part1": 11, "part2": 31}', or
+ * '{"error": "..."}' if the solver fails
+ */
+#[wasm_bindgen]
+pub fn solve(year: &str, day: u8, input: &str) -> String
+{
+    match solutions::solve(year, day, Box::new(input.lines())) {
+        Ok(solution) => json_object([("part1", solution.part1.to_string()), ("part2", solution.part2.to_string())]),
+        Err(e) => json_error(&e.to_string())
+    }
+}
+
+// The number of days in each year that was compiled in, as '{"2024": 25}', for the page to offer
+#[wasm_bindgen]
+pub fn years() -> String
+{
+    json_object(solutions::YEARS.iter()
+        .filter(|year| solutions::compiled_in(year))
+        .map(|year| (year, solutions::days(year).unwrap().to_string())))
+}
+
+#[cfg(all(test, feature = "y2024", feature = "y2025"))]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn test_solve()
+    {
+        assert_eq!(solve("2024", 1, "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n"), "{\"part1\": 11, \"part2\": 31}");
+        assert_eq!(solve("2025", 1, ""), "{\"error\": \"not started\"}");
+        assert_eq!(years(), "{\"2024\": 25, \"2025\": 12}");
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::runner::{input_files, is_input_file};
+use crate::solutions;
+
+// Editors often write a file in several steps, so events this close together are handled once
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+type Answers = Result<(isize, isize), String>;
+
+fn solve_file(year: &str, day: u8, path: &Path) -> Answers
+{
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    solutions::solve(year, day, Box::new(text.lines()))
+        .map(|solution| (solution.part1, solution.part2))
+        .map_err(|e| e.to_string())
+}
+
+// The answers, followed by how they differ from the last time the same file was solved
+fn describe(current: &Answers, previous: Option<&Answers>) -> String
+{
+    let answers = match current {
+        Ok((part1, part2)) => format!("Part 1: {} | Part 2: {}", part1, part2),
+        Err(e) => format!("Error: {}", e)
+    };
+
+    let changes = match (previous, current) {
+        (None, _) => String::new(),
+        (Some(previous), current) if previous == current => "(unchanged)".to_string(),
+        (Some(Ok(previous)), Ok(current)) => {
+            let changed: Vec<String> = [(1, previous.0, current.0), (2, previous.1, current.1)].iter()
+                .filter(|(_, was, now)| was != now)
+                .map(|(part, was, _)| format!("part {} was {}", part, was))
+                .collect();
+            format!("({})", changed.join(", "))
+        },
+        (Some(Ok((part1, part2))), Err(_)) => format!("(was Part 1: {} | Part 2: {})", part1, part2),
+        (Some(Err(_)), Ok(_)) => "(was an error)".to_string(),
+        (Some(Err(e)), Err(_)) => format!("(was Error: {})", e)
+    };
+
+    format!("{:<40} {}", answers, changes).trim_end().to_string()
+}
+
+struct DayWatch
+{
+    year: String,
+    day: u8,
+    input_dir: PathBuf,
+    source: PathBuf,
+    previous: HashMap<PathBuf, Answers>
+}
+
+impl DayWatch
+{
+    fn solve_all(&mut self)
+    {
+        let files = input_files(&self.input_dir, self.day);
+        if files.is_empty()
+        {
+            println!("  No input in {}", self.input_dir.display());
+        }
+
+        for path in files
+        {
+            let answers = solve_file(&self.year, self.day, &path);
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            println!("  {:<24} {}", name, describe(&answers, self.previous.get(&path)));
+            self.previous.insert(path, answers);
+        }
+    }
+
+    // Runs the day's own tests with cargo, which rebuilds with the changed source first
+    fn run_tests(&self)
+    {
+        let filter = format!("solutions::yr{}::day{:02}", self.year, self.day);
+        match Command::new("cargo").args(["test", "--quiet", &filter]).status() {
+            Ok(status) if status.success() => println!("  Tests passed"),
+            Ok(_) => println!("  Tests failed"),
+            Err(e) => println!("  Couldn't run cargo: {}", e)
+        }
+    }
+
+    fn handle(&mut self, paths: &[PathBuf])
+    {
+        let changed_input = paths.iter().find(|path| {
+            path.file_name().and_then(|name| name.to_str()).is_some_and(|name| is_input_file(name, &format!("day{:02}", self.day)))
+        });
+        if let Some(path) = changed_input
+        {
+            println!("{} changed", path.display());
+            self.solve_all();
+        }
+
+        // Only the input and source directories are watched, and only the source is a .rs file
+        if paths.iter().any(|path| path.file_name() == self.source.file_name())
+        {
+            println!("{} changed", self.source.display());
+            self.run_tests();
+        }
+    }
+}
+
+/**
+ * Solves the day, then keeps watching for changes. Changes to its input or examples solve it
+ * again, and changes to its source file run its tests. Only the operating system's own file
+ * notifications are used, nothing is polled
+ */
+pub fn watch(year: &str, day: u8) -> anyhow::Result<()>
+{
+    let mut day_watch = DayWatch {
+        year: year.to_string(),
+        day,
+        input_dir: PathBuf::from(format!("./input/{year}")),
+        source: PathBuf::from(format!("./src/solutions/yr{year}/day{:02}.rs", day)),
+        previous: HashMap::new()
+    };
+
+    let (sender, receiver) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(&day_watch.input_dir, RecursiveMode::NonRecursive)?;
+    if let Some(source_dir) = day_watch.source.parent().filter(|dir| dir.exists())
+    {
+        watcher.watch(source_dir, RecursiveMode::NonRecursive)?;
+    }
+
+    println!("Watching day {:02} of {}, press Ctrl+C to stop", day, year);
+    day_watch.solve_all();
+
+    while let Ok(event) = receiver.recv()
+    {
+        let mut paths = Vec::new();
+        let mut collect = |event: notify::Result<Event>| {
+            if let Ok(event) = event
+            {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                {
+                    paths.extend(event.paths);
+                }
+            }
+        };
+
+        collect(event);
+        while let Ok(event) = receiver.recv_timeout(SETTLE_TIME)
+        {
+            collect(event);
+        }
+
+        if !paths.is_empty()
+        {
+            day_watch.handle(&paths);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    fn test_describe()
+    {
+        assert_eq!(describe(&Ok((161, 48)), None), "Part 1: 161 | Part 2: 48");
+        assert_eq!(describe(&Ok((161, 48)), Some(&Ok((161, 48)))), format!("{:<40} (unchanged)", "Part 1: 161 | Part 2: 48"));
+        assert_eq!(describe(&Ok((161, 48)), Some(&Ok((150, 48)))), format!("{:<40} (part 1 was 150)", "Part 1: 161 | Part 2: 48"));
+        assert_eq!(describe(&Err("bad".to_string()), Some(&Ok((1, 2)))), format!("{:<40} (was Part 1: 1 | Part 2: 2)", "Error: bad"));
+    }
+}
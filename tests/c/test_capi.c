@@ -0,0 +1,53 @@
+/* Solves the 2024 day 1 sample through the C API, exiting with a non-zero status if anything is wrong */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "advent_of_code.h"
+
+static int failures = 0;
+
+static void check(int condition, const char *message)
+{
+    if (!condition)
+    {
+        fprintf(stderr, "FAILED: %s\n", message);
+        failures++;
+    }
+}
+
+int main(void)
+{
+    const char *sample = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    AocResult result;
+
+    check(aoc_api_version() == AOC_API_VERSION, "the library matches the header");
+
+    check(aoc_solve(2024, 1, (const uint8_t *)sample, strlen(sample), &result) == AOC_STATUS_OK, "2024 day 1 solves");
+    check(result.part1 == 11 && result.part2 == 31, "2024 day 1 gives 11 and 31");
+    check(result.error == NULL, "a solved day has no error");
+    aoc_free_result(&result);
+
+    check(aoc_solve(2025, 1, NULL, 0, &result) == AOC_STATUS_NOT_STARTED, "2025 day 1 is not started");
+    check(result.error != NULL && strcmp(result.error, "not started") == 0, "not started has a message");
+    aoc_free_result(&result);
+    aoc_free_result(&result);
+
+    check(aoc_solve(2024, 1, (const uint8_t *)"\xff", 1, &result) == AOC_STATUS_INVALID_ARGUMENT, "bad UTF-8 is refused");
+    aoc_free_result(&result);
+
+    check(aoc_solve(1999, 1, (const uint8_t *)sample, strlen(sample), &result) == AOC_STATUS_ERROR, "unknown years fail");
+    printf("1999: %s\n", result.error);
+    aoc_free_result(&result);
+
+    uint8_t days[25];
+    size_t count = aoc_list_days(2024, days, sizeof(days));
+    check(count == 6 && days[0] == 1 && days[5] == 6, "2024 has days 1 to 6 started");
+    check(aoc_list_days(2025, NULL, 0) == 0, "2025 has no days started");
+
+    if (failures == 0)
+    {
+        printf("All C API checks passed\n");
+    }
+    return failures == 0 ? 0 : 1;
+}
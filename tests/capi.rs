@@ -0,0 +1,45 @@
+#![cfg(all(feature = "capi", feature = "y2024", feature = "y2025", target_os = "linux"))]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+// Builds tests/c/test_capi.c against the shared library cargo has just built, and runs it
+#[test]
+fn test_c_program()
+{
+    // The test binary is built next to the library, in target/<profile>/deps
+    let library_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let program = library_dir.join("test_capi");
+
+    let status = Command::new(env::var("CC").unwrap_or("cc".to_string()))
+        .arg(manifest_dir.join("tests/c/test_capi.c"))
+        .arg("-Wall").arg("-Werror")
+        .arg("-I").arg(manifest_dir.join("include"))
+        .arg("-L").arg(&library_dir)
+        .arg("-ladvent_of_code")
+        .arg("-o").arg(&program)
+        .status()
+        .expect("Unable to run the C compiler");
+    assert!(status.success(), "test_capi.c didn't compile");
+
+    let output = Command::new(&program)
+        .env("LD_LIBRARY_PATH", &library_dir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("1999: 1999 Not Implemented"), "{}", stdout);
+}
+
+// The build generates the header afresh, and the one C programs include has to keep up with it
+#[test]
+fn test_header_up_to_date()
+{
+    let generated = PathBuf::from(env!("OUT_DIR")).join("advent_of_code.h");
+    let header = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("include/advent_of_code.h");
+    assert!(fs::read_to_string(&generated).unwrap() == fs::read_to_string(&header).unwrap(),
+        "include/advent_of_code.h is out of date, copy {} over it", generated.display());
+}
@@ -0,0 +1,89 @@
+use advent_of_code::columns::Columns;
+use advent_of_code::runner::get_solution;
+use advent_of_code::solver::{Solution, SolutionResult, Solver};
+use advent_of_code::visualise::{Colour, Frame, Grid};
+
+// A solver written outside the crate, counting the lines and characters it is given
+struct LineCounter {}
+
+impl Solver for LineCounter
+{
+    fn solve_impl(lines: Vec<&str>) -> SolutionResult
+    {
+        Ok(Solution {
+            part1: lines.len() as isize,
+            part2: lines.iter().map(|line| line.len() as isize).sum()
+        })
+    }
+}
+
+#[test]
+fn test_own_solver()
+{
+    let solution = LineCounter::solve(Box::new("\n  ab\n\ncde  \n".split('\n'))).unwrap();
+    assert_eq!((solution.part1, solution.part2), (2, 5));
+}
+
+#[cfg(all(feature = "y2024", feature = "y2025"))]
+#[test]
+fn test_registry()
+{
+    use advent_of_code::solutions;
+    use advent_of_code::solutions::yr2024::day01::SolverDay01;
+    use advent_of_code::solver::NotStarted;
+
+    let sample = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+
+    let solution = solutions::solve("2024", 1, Box::new(sample.lines())).unwrap();
+    assert_eq!((solution.part1, solution.part2), (11, 31));
+
+    let solution = SolverDay01::solve(Box::new(sample.lines())).unwrap();
+    assert_eq!((solution.part1, solution.part2), (11, 31));
+
+    assert!(solutions::solve("2025", 1, Box::new(sample.lines())).unwrap_err().is::<NotStarted>());
+
+    assert!(solutions::started("2024", 1));
+    assert!(!solutions::started("2024", 7));
+    assert!(!solutions::started("2024", 26));
+    assert!(!solutions::started("2025", 1));
+    assert!(!solutions::started("1999", 1));
+}
+
+// Days of a year that isn't built can't be started, whatever its registry says
+#[cfg(all(feature = "y2024", not(feature = "y2025")))]
+#[test]
+fn test_started_not_compiled_in()
+{
+    use advent_of_code::solutions;
+
+    assert!(solutions::started("2024", 1));
+    assert!(!solutions::started("2025", 1));
+}
+
+#[cfg(feature = "y2024")]
+#[test]
+fn test_runner()
+{
+    let solution = get_solution("2024", 1).unwrap();
+    assert_eq!(solution.part1, 1830467);
+}
+
+#[cfg(not(feature = "y2024"))]
+#[test]
+fn test_year_not_compiled_in()
+{
+    let error = get_solution("2024", 1).unwrap_err();
+    assert_eq!(error.to_string(), "2024 not compiled in, build with '--features y2024' to include it");
+}
+
+#[test]
+fn test_shared_utilities()
+{
+    let columns = Columns::from_lines(2, ["1 2", "3 4"]);
+    assert_eq!(columns.distance(0, 1), Some(2));
+
+    let mut grid = Grid::from_display(&"ab\ncd");
+    grid.set_colour(1, 1, Colour::Green);
+    let frame = Frame::grid("caption", grid);
+    assert_eq!(frame.caption, "caption");
+}